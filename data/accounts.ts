@@ -10,19 +10,25 @@ import {
 import { toAnchorFriendlyID } from './common'
 import { Recipe } from './recipes'
 import { QuestData } from './quests'
+import { ItemData } from './items'
 
 const CONFIG_PREFIX = 'arising_config_account'
 const FORGE_RECIPE_PREFIX = 'arising_forge_recipe'
-const CRAFT_RECIPE_PREFIX = 'arsing_craft'
+const CRAFT_RECIPE_PREFIX = 'arising_craft_recipe'
 const QUEST_PREFIX = 'arising_quest'
+const ITEM_DEFINITION_PREFIX = 'arising_item_definition'
+const ITEM_PREFIX = 'arising_item'
+const ITEM_AUTHORITY_PREFIX = 'arising_item_authority'
+const MATERIAL_MINT_PREFIX = 'arising_material_mint'
+const MATERIAL_AUTHORITY_PREFIX = 'arising_material_authority'
 
 const METADATA_PREFIX = 'metadata'
 const MASTER_EDITION_PREFIX = 'edition'
 
 const CHARACTER_PREFIX = 'arising_character_account'
-const CHARACTER_MATERIAL_PREFIX = 'arising_character_materials_account'
+const CHARACTER_MATERIAL_PREFIX = 'arising_character_materials'
 const CHARACTER_SLOTS_PREFIX = 'arising_character_slots_account'
-const CHARACTER_EQUIPMENT_PREFIX = 'arising_character_equipment_account'
+const CHARACTER_EQUIPMENT_PREFIX = 'arising_character_equipment'
 
 export const TOKEN_METADATA_PROGRAM_ID = new PublicKey(TOKEN_METADATA_PROGRAM)
 
@@ -90,6 +96,32 @@ export const getProgramCharacterEquipmentAccount = async (
     return { account, bump }
 }
 
+// Returns all the program accounts of a character from a mint
+export const getProgramCharacterAccounts = async (
+    mint: PublicKey,
+    program: Program<Arising>
+): Promise<{
+    character: PublicKey
+    characterMaterials: PublicKey
+    characterSlots: PublicKey
+    characterEquipment: PublicKey
+}> => {
+    const { account: character } = await getProgramCharacterAccount(
+        mint,
+        program
+    )
+    const { account: characterMaterials } =
+        await getProgramCharacterMaterialsAccount(mint, program)
+    const { account: characterSlots } = await getProgramCharacterSlotsAccount(
+        mint,
+        program
+    )
+    const { account: characterEquipment } =
+        await getProgramCharacterEquipmentAccount(mint, program)
+
+    return { character, characterMaterials, characterSlots, characterEquipment }
+}
+
 // Returns the program forge recipe account from a recipe ID
 export const getProgramForgeRecipeAccount = async (
     recipe: Recipe,
@@ -116,6 +148,73 @@ export const getProgramCraftRecipeAccount = async (
     return { account, bump }
 }
 
+// Returns the program item definition account from an item ID
+export const getProgramItemDefinitionAccount = async (
+    item: ItemData,
+    program: Program<Arising>
+): Promise<{ account: PublicKey; bump: number }> => {
+    const [account, bump] = await PublicKey.findProgramAddress(
+        [Buffer.from(ITEM_DEFINITION_PREFIX), toAnchorFriendlyID(item.id)],
+        program.programId
+    )
+
+    return { account, bump }
+}
+
+// Returns the program item account from the item mint
+export const getProgramItemAccount = async (
+    mint: PublicKey,
+    program: Program<Arising>
+): Promise<{ account: PublicKey; bump: number }> => {
+    const [account, bump] = await PublicKey.findProgramAddress(
+        [Buffer.from(ITEM_PREFIX), mint.toBuffer()],
+        program.programId
+    )
+
+    return { account, bump }
+}
+
+// Returns the program account signing as the item mints authority
+export const getProgramItemAuthorityAccount = async (
+    program: Program<Arising>
+): Promise<{ account: PublicKey; bump: number }> => {
+    const [account, bump] = await PublicKey.findProgramAddress(
+        [Buffer.from(ITEM_AUTHORITY_PREFIX)],
+        program.programId
+    )
+
+    return { account, bump }
+}
+
+// Returns the program material mint registry account from a material type and ID
+export const getProgramMaterialMintAccount = async (
+    materialType: number,
+    material: number,
+    program: Program<Arising>
+): Promise<{ account: PublicKey; bump: number }> => {
+    const materialTypeBuffer = Buffer.alloc(2)
+    materialTypeBuffer.writeUInt16LE(materialType)
+
+    const [account, bump] = await PublicKey.findProgramAddress(
+        [Buffer.from(MATERIAL_MINT_PREFIX), materialTypeBuffer, toAnchorFriendlyID(material)],
+        program.programId
+    )
+
+    return { account, bump }
+}
+
+// Returns the program account signing as the material mints authority
+export const getProgramMaterialAuthorityAccount = async (
+    program: Program<Arising>
+): Promise<{ account: PublicKey; bump: number }> => {
+    const [account, bump] = await PublicKey.findProgramAddress(
+        [Buffer.from(MATERIAL_AUTHORITY_PREFIX)],
+        program.programId
+    )
+
+    return { account, bump }
+}
+
 // Returns the program quest account from a quest ID
 export const getProgramQuestAccount = async (
     quest: QuestData,
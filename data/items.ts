@@ -27,7 +27,7 @@ export enum ITEM_TYPE {
     CAPE,
 }
 
-interface ItemData {
+export interface ItemData {
     id: ITEM
     type: ITEM_TYPE
     additions: { stats: BaseStats; attributes: BaseAttributes }
@@ -1,15 +1,19 @@
-use anchor_lang::prelude::*;
-use anchor_spl::token::TokenAccount;
+use anchor_lang::{ prelude::*, solana_program::sysvar::slot_hashes };
+use anchor_spl::{ metadata::MetadataAccount, token::{ self, Burn, Mint, Token, TokenAccount } };
+use mpl_token_metadata::state::{ Metadata, PREFIX as METADATA_PREFIX };
 
 use crate::codex::*;
 use crate::config::*;
 use crate::errors::*;
+use crate::math::*;
+use crate::operators::*;
+use crate::random::*;
 use crate::utils::*;
 
-const CHARACTER_PREFIX: &str = "arising_character_account";
-const CHARACTER_MATERIAL_PREFIX: &str = "arising_character_materials_account";
-const CHARACTER_SLOTS_PREFIX: &str = "arising_character_slots_account";
-const CHARACTER_EQUIPMENT_PREFIX: &str = "arising_character_equipment_account";
+pub const CHARACTER_PREFIX: &str = "arising_character_account";
+pub const CHARACTER_MATERIAL_PREFIX: &str = "arising_character_materials";
+pub const CHARACTER_SLOTS_PREFIX: &str = "arising_character_slots_account";
+pub const CHARACTER_EQUIPMENT_PREFIX: &str = "arising_character_equipment";
 
 #[inline(always)]
 pub fn is_mint_owner(
@@ -36,8 +40,8 @@ pub fn is_mint_owner(
 }
 
 #[inline(always)]
-pub fn can_refresh(config: &Account<Config>, character: &Account<Character>) -> bool {
-    return character.last_refresh + config.seconds_between_refreshes < now();
+pub fn can_refresh(config: &Account<Config>, character: &Account<Character>) -> Result<bool> {
+    return Ok(checked_add(character.last_refresh, config.seconds_between_refreshes)? < now());
 }
 
 #[inline(always)]
@@ -52,8 +56,8 @@ pub fn refresh(character: &mut Account<Character>) {
 }
 
 #[inline(always)]
-pub fn can_refresh_with_token(config: &Account<Config>, character: &Account<Character>) -> bool {
-    return character.last_refresh_with_refresher + config.seconds_between_paid_refreshes < now();
+pub fn can_refresh_with_token(config: &Account<Config>, character: &Account<Character>) -> Result<bool> {
+    return Ok(checked_add(character.last_refresh_with_refresher, config.seconds_between_paid_refreshes)? < now());
 }
 
 #[inline(always)]
@@ -64,19 +68,76 @@ pub fn refresh_with_token(character: &mut Account<Character>) {
 
     character.last_refresh_with_refresher = now();
 
-    // TODO burn token.
+    return;
+}
+
+#[inline(always)]
+pub fn can_respec(config: &Account<Config>, character: &Account<Character>) -> Result<bool> {
+    return Ok(checked_add(character.last_respec, config.seconds_between_respecs)? < now());
 }
 
+/// Resets the base stats so every assignable point can be assigned again,
+/// sacrificed points stay lost.
 #[inline(always)]
-pub fn can_assign_points(character: &Account<Character>, points: &BaseStats) -> bool {
-    let sum = points.might + points.speed + points.intellect;
+pub fn respec(character: &mut Account<Character>) {
+    character.base_stats = BaseStats::default();
+    character.pool_stats = BaseStats::default();
+
+    character.last_respec = now();
+
+    return;
+}
+
+/// Burns the respec cost from the payer token account.
+pub fn burn_respec_cost(accounts: &CharacterRespec, amount: u64) -> Result<()> {
+    let cpi_accounts = Burn {
+        mint: accounts.respec_mint.to_account_info(),
+        from: accounts.respec_token_account.to_account_info(),
+        authority: accounts.payer.to_account_info(),
+    };
+
+    let cpi_context = CpiContext::new(accounts.token_program.to_account_info(), cpi_accounts);
+
+    return token::burn(cpi_context, amount);
+}
+
+/// Burns a single refresher token from the payer token account.
+pub fn burn_refresher(accounts: &CharacterRefreshWithToken) -> Result<()> {
+    let cpi_accounts = Burn {
+        mint: accounts.refresher_mint.to_account_info(),
+        from: accounts.refresher_token_account.to_account_info(),
+        authority: accounts.payer.to_account_info(),
+    };
+
+    let cpi_context = CpiContext::new(accounts.token_program.to_account_info(), cpi_accounts);
+
+    return token::burn(cpi_context, 1);
+}
+
+#[inline(always)]
+fn sum_points(points: &BaseStats) -> Result<u16> {
+    return checked_add(checked_add(points.might, points.speed)?, points.intellect);
+}
+
+/// Only the points not assigned yet can be assigned.
+#[inline(always)]
+pub fn can_assign_points(character: &Account<Character>, points: &BaseStats) -> Result<bool> {
+    let sum = checked_add(sum_points(points)?, get_character_assigned_points(character)?)?;
     let available = get_character_assignable_points(character);
-    return sum <= available;
+    return Ok(sum <= available);
 }
 
+/// The points already assigned, characters start with empty base stats.
+#[inline(always)]
+pub fn get_character_assigned_points(character: &Account<Character>) -> Result<u16> {
+    return sum_points(&character.base_stats);
+}
+
+/// The points a character can assign, sacrificed points are lost for good.
 #[inline(always)]
 pub fn get_character_assignable_points(character: &Account<Character>) -> u16 {
-    return 6 + character.level;
+    let total = 6 + (character.level as u32);
+    return total.saturating_sub(character.sacrificed_points) as u16;
 }
 
 pub fn can_consume(character: &Account<Character>, points: &BaseStats) -> bool {
@@ -95,12 +156,48 @@ pub fn can_consume(character: &Account<Character>, points: &BaseStats) -> bool {
     return true;
 }
 
-pub fn consume_points(character: &mut Account<Character>, points: &BaseStats) {
-    character.pool_stats.might -= points.might;
-    character.pool_stats.speed -= points.speed;
-    character.pool_stats.intellect -= points.intellect;
+pub fn can_sacrifice(character: &Account<Character>, points: &BaseStats) -> bool {
+    if character.base_stats.might < points.might {
+        return false;
+    }
 
-    return;
+    if character.base_stats.speed < points.speed {
+        return false;
+    }
+
+    if character.base_stats.intellect < points.intellect {
+        return false;
+    }
+
+    return true;
+}
+
+/// Permanently lowers the base stats, the pool never stays above them.
+pub fn sacrifice_points(character: &mut Account<Character>, points: &BaseStats) -> Result<()> {
+    character.base_stats.might = checked_sub(character.base_stats.might, points.might)?;
+    character.base_stats.speed = checked_sub(character.base_stats.speed, points.speed)?;
+    character.base_stats.intellect = checked_sub(character.base_stats.intellect, points.intellect)?;
+
+    character.pool_stats.might = character.pool_stats.might.min(character.base_stats.might);
+    character.pool_stats.speed = character.pool_stats.speed.min(character.base_stats.speed);
+    character.pool_stats.intellect = character.pool_stats.intellect.min(character.base_stats.intellect);
+
+    let sacrificed = checked_add(
+        checked_add(points.might as u32, points.speed as u32)?,
+        points.intellect as u32
+    )?;
+
+    character.sacrificed_points = checked_add(character.sacrificed_points, sacrificed)?;
+
+    return Ok(());
+}
+
+pub fn consume_points(character: &mut Account<Character>, points: &BaseStats) -> Result<()> {
+    character.pool_stats.might = checked_sub(character.pool_stats.might, points.might)?;
+    character.pool_stats.speed = checked_sub(character.pool_stats.speed, points.speed)?;
+    character.pool_stats.intellect = checked_sub(character.pool_stats.intellect, points.intellect)?;
+
+    return Ok(());
 }
 
 #[inline(always)]
@@ -109,7 +206,7 @@ pub fn consume_materials(
     materials: &[u32; 10],
     amounts: &[u32; 10],
     types: &[u16; 10],
-) {
+) -> Result<()> {
     let mut i: usize = 0;
 
     loop {
@@ -123,21 +220,25 @@ pub fn consume_materials(
 
         if material != 0 {
             if material_type == (ResourceType::Basic as u16) {
-                character_materials.basic[(material - 1) as usize] -= amount;
+                let index = (material - 1) as usize;
+                character_materials.basic[index] = checked_sub(character_materials.basic[index], amount)?;
             }
 
             if material_type == (ResourceType::Raw as u16) {
-                character_materials.raw[(material - 1) as usize] -= amount;
+                let index = (material - 1) as usize;
+                character_materials.raw[index] = checked_sub(character_materials.raw[index], amount)?;
             }
         }
 
         i += 1;
     }
+
+    return Ok(());
 }
 
 #[inline(always)]
 pub fn has_enough_materials(
-    character_materials: &mut Account<CharacterMaterials>,
+    character_materials: &Account<CharacterMaterials>,
     materials: &[u32; 10],
     amounts: &[u32; 10],
     types: &[u16; 10],
@@ -174,12 +275,19 @@ pub fn has_enough_materials(
     return true;
 }
 
+/// Adds the experience scaled by the current multiplier and updates the level.
 #[inline(always)]
-pub fn add_experience(character: &mut Account<Character>, experience: u64) {
-    character.experience += experience;
+pub fn add_experience(
+    character: &mut Account<Character>,
+    config: &Account<Config>,
+    experience: u64
+) -> Result<()> {
+    let experience = checked_mul(experience, experience_multiplier(config, now()))?;
+
+    character.experience = checked_add(character.experience, experience)?;
     character.level = get_level(character.experience);
 
-    return;
+    return Ok(());
 }
 
 #[inline(always)]
@@ -196,16 +304,95 @@ pub fn is_slot_claimable(slot: &CharacterSlot) -> bool {
     return slot.cooldown <= now() && !slot.last_task_claimed && slot.last_task_id != 0;
 }
 
+/// Stores the hash of the slot after the commitment of a pending task while the SlotHashes
+/// sysvar still has it, so the task can be claimed once the hash aged out of the sysvar.
+pub fn store_slot_hash(slot: &mut CharacterSlot, slot_hashes: &[u8]) -> bool {
+    if slot.last_task_id == 0 || slot.last_task_claimed || slot.slot_hash_stored {
+        return false;
+    }
+
+    return match slot_hash_after(slot_hashes, slot.commitment_slot) {
+        SlotHashLookup::Found(hash) => {
+            slot.slot_hash = hash;
+            slot.slot_hash_stored = true;
+            true
+        }
+        _ => false,
+    };
+}
+
+/// The slot hash a task settles with, the stored one or the one still in the SlotHashes sysvar.
+pub fn task_slot_hash(slot: &CharacterSlot, slot_hashes: &[u8]) -> SlotHashLookup {
+    if slot.slot_hash_stored {
+        return SlotHashLookup::Found(slot.slot_hash);
+    }
+
+    return slot_hash_after(slot_hashes, slot.commitment_slot);
+}
+
+/// Checks the NFT is a verified item of the Arising collection.
+pub fn is_arising_collection(metadata: &Metadata, config: &Config) -> bool {
+    return match &metadata.collection {
+        Some(collection) => collection.verified && collection.key == config.character_collection,
+        None => false,
+    };
+}
+
+/// Checks the Arising creator verified the NFT.
+pub fn is_arising_creator(metadata: &Metadata, config: &Config) -> bool {
+    let creators = match &metadata.data.creators {
+        Some(creators) => creators,
+        None => {
+            return false;
+        }
+    };
+
+    let mut i: usize = 0;
+
+    loop {
+        if i >= creators.len() {
+            break;
+        }
+
+        if creators[i].verified && creators[i].address == config.character_creator {
+            return true;
+        }
+
+        i += 1;
+    }
+
+    return false;
+}
+
 #[derive(Accounts)]
 #[instruction(mint: Pubkey, bump: u8)]
 pub struct AddCharacter<'info> {
-    #[account(mut,
-        constraint = payer.key() == config.authority @ ArisingError::InvalidAuthority)]
+    #[account(mut)]
     payer: Signer<'info>,
 
-    #[account(mut)]
+    #[account(
+        constraint = is_authorized(&config, authority.key(), &operator, Role::Characters) @ ArisingError::InvalidAuthority)]
+    authority: Signer<'info>,
+
+    #[account(mut,
+        constraint = config.characters < config.max_characters @ ArisingError::CharacterCapReached)]
     pub config: Account<'info, Config>,
 
+    pub operator: Option<Account<'info, Operator>>,
+
+    #[account(
+        address = mint @ CharacterError::InvalidCharacterMint,
+        constraint = character_mint.supply == 1 && character_mint.decimals == 0 @ CharacterError::InvalidCharacterMint)]
+    pub character_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [METADATA_PREFIX.as_bytes(), mpl_token_metadata::ID.as_ref(), mint.as_ref()],
+        seeds::program = mpl_token_metadata::ID,
+        bump,
+        constraint = is_arising_collection(&character_metadata, &config) @ CharacterError::InvalidCharacterCollection,
+        constraint = is_arising_creator(&character_metadata, &config) @ CharacterError::InvalidCharacterCreator)]
+    pub character_metadata: Account<'info, MetadataAccount>,
+
     #[account(
         init,
         payer = payer,
@@ -215,6 +402,33 @@ pub struct AddCharacter<'info> {
     )]
     pub character: Account<'info, Character>,
 
+    #[account(
+        init,
+        payer = payer,
+        seeds = [CHARACTER_MATERIAL_PREFIX.as_bytes(), &mint.to_bytes()],
+        bump,
+        space = CHARACTER_MATERIALS_ACCOUNT_SIZE
+    )]
+    pub character_materials: Account<'info, CharacterMaterials>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [CHARACTER_SLOTS_PREFIX.as_bytes(), &mint.to_bytes()],
+        bump,
+        space = CHARACTER_SLOTS_ACCOUNT_SIZE
+    )]
+    pub character_slots: Account<'info, CharacterSlots>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [CHARACTER_EQUIPMENT_PREFIX.as_bytes(), &mint.to_bytes()],
+        bump,
+        space = CHARACTER_EQUIPMENT_SIZE
+    )]
+    pub character_equipment: Account<'info, CharacterEquipment>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -224,19 +438,28 @@ pub struct CharacterAccess<'info> {
         constraint = is_mint_owner(character.mint, payer.key(), &character_token_account) @ ArisingError::InvalidOwner)]
     payer: Signer<'info>,
 
-    #[account(mut)]
+    #[account(mut,
+        seeds = [CHARACTER_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
     pub character: Account<'info, Character>,
 
-    #[account(mut)]
+    #[account(mut,
+        seeds = [CHARACTER_MATERIAL_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
     pub character_materials: Account<'info, CharacterMaterials>,
 
-    #[account(mut)]
+    #[account(mut,
+        seeds = [CHARACTER_SLOTS_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
     pub character_slots: Account<'info, CharacterSlots>,
 
-    #[account(mut)]
+    #[account(mut,
+        seeds = [CHARACTER_EQUIPMENT_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
     pub character_equipment: Account<'info, CharacterEquipment>,
 
-    #[account(mut)]
+    #[account(mut,
+        constraint = !is_paused(&config, Subsystem::Game) @ ArisingError::Paused)]
     pub config: Account<'info, Config>,
 
     #[account(mut)]
@@ -245,6 +468,102 @@ pub struct CharacterAccess<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct StoreSlotHashes<'info> {
+    #[account(
+        seeds = [CHARACTER_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character: Account<'info, Character>,
+
+    #[account(mut,
+        seeds = [CHARACTER_SLOTS_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character_slots: Account<'info, CharacterSlots>,
+
+    /// CHECK: the address is checked to be the SlotHashes sysvar and the data is parsed.
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CharacterRefresh<'info> {
+    #[account(mut,
+        constraint = is_mint_owner(character.mint, payer.key(), &character_token_account) @ ArisingError::InvalidOwner)]
+    payer: Signer<'info>,
+
+    #[account(mut,
+        seeds = [CHARACTER_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character: Account<'info, Character>,
+
+    #[account(
+        constraint = !is_paused(&config, Subsystem::Refresh) @ ArisingError::Paused)]
+    pub config: Account<'info, Config>,
+
+    pub character_token_account: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct CharacterRefreshWithToken<'info> {
+    #[account(mut,
+        constraint = is_mint_owner(character.mint, payer.key(), &character_token_account) @ ArisingError::InvalidOwner)]
+    payer: Signer<'info>,
+
+    #[account(mut,
+        seeds = [CHARACTER_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character: Account<'info, Character>,
+
+    #[account(
+        constraint = !is_paused(&config, Subsystem::Refresh) @ ArisingError::Paused)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub character_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut,
+        address = config.refresher_mint @ CharacterError::InvalidRefresherMint)]
+    pub refresher_mint: Account<'info, Mint>,
+
+    #[account(mut,
+        constraint = refresher_token_account.mint == config.refresher_mint @ CharacterError::InvalidRefresherMint,
+        constraint = refresher_token_account.owner == payer.key() @ ArisingError::InvalidOwner,
+        constraint = refresher_token_account.amount >= 1 @ CharacterError::NotEnoughRefresherTokens)]
+    pub refresher_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CharacterRespec<'info> {
+    #[account(mut,
+        constraint = is_mint_owner(character.mint, payer.key(), &character_token_account) @ ArisingError::InvalidOwner)]
+    payer: Signer<'info>,
+
+    #[account(mut,
+        seeds = [CHARACTER_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character: Account<'info, Character>,
+
+    #[account(
+        constraint = !is_paused(&config, Subsystem::Game) @ ArisingError::Paused)]
+    pub config: Account<'info, Config>,
+
+    pub character_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut,
+        address = config.respec_mint @ CharacterError::InvalidRespecMint)]
+    pub respec_mint: Account<'info, Mint>,
+
+    #[account(mut,
+        constraint = respec_token_account.mint == config.respec_mint @ CharacterError::InvalidRespecMint,
+        constraint = respec_token_account.owner == payer.key() @ ArisingError::InvalidOwner,
+        constraint = respec_token_account.amount >= config.respec_cost @ CharacterError::NotEnoughRespecTokens)]
+    pub respec_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
 pub const CHARACTER_ACCOUNT_SIZE: usize = 8 + // discriminator
     16 + // level
     32 + // mint
@@ -253,7 +572,8 @@ pub const CHARACTER_ACCOUNT_SIZE: usize = 8 + // discriminator
     64 + // experience
     64 + // last_refresh
     64 + // last_refresh_with_refresher
-    32; // sacrificed_points
+    32 + // sacrificed_points
+    64; // last_respec
 
 #[account]
 pub struct Character {
@@ -265,6 +585,7 @@ pub struct Character {
     pub last_refresh: u64,
     pub last_refresh_with_refresher: u64,
     pub sacrificed_points: u32,
+    pub last_respec: u64,
 }
 
 pub const CHARACTER_MATERIALS_ACCOUNT_SIZE: usize = 8 + // discriminator
@@ -279,13 +600,23 @@ pub struct CharacterMaterials {
 
 pub const CHARACTER_SLOT_SIZE: usize = 64 + // cooldown
     32 + // last_task_id
-    1; // last_task_claimed
+    1 + // last_task_claimed
+    256 + // commitment
+    64 + // commitment_slot
+    32 + // item
+    256 + // slot_hash
+    1; // slot_hash_stored
 
 #[derive(Clone, AnchorSerialize, AnchorDeserialize)]
 pub struct CharacterSlot {
     pub cooldown: u64,
     pub last_task_id: u32,
     pub last_task_claimed: bool,
+    pub commitment: [u8; 32],
+    pub commitment_slot: u64,
+    pub item: Pubkey,
+    pub slot_hash: [u8; 32],
+    pub slot_hash_stored: bool,
 }
 
 pub const CHARACTER_SLOTS_ACCOUNT_SIZE: usize = 8 + // discriminator
@@ -303,7 +634,8 @@ pub struct CharacterSlots {
 }
 
 /// The character informationsize in bytes.
-pub const CHARACTER_EQUIPMENT_SIZE: usize = 32 + // helmer
+pub const CHARACTER_EQUIPMENT_SIZE: usize = 8 + // discriminator
+    32 + // helmet
     32 + // shoulder_guards
     32 + // arm_guards
     32 + // hands
@@ -157,13 +157,17 @@ static LEVELS: [Level; 150] = [
 pub fn get_level(experience: u64) -> u16 {
     let mut n: u16 = 0;
 
+    if experience < LEVELS[0].min {
+        return 0;
+    }
+
     loop {
+        if (n as usize) >= LEVELS.len() {
+            return 150;
+        }
         if experience >= LEVELS[n as usize].min && experience < LEVELS[n as usize].max {
             return n;
         }
-        if n > 150 {
-            return 150;
-        }
         n += 1;
     }
 }
@@ -180,7 +184,7 @@ pub const BASE_STATS_SIZE: usize =
     16; // intellect
 
 /// The struct for character stats.
-#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+#[derive(Clone, Default, AnchorSerialize, AnchorDeserialize)]
 pub struct BaseStats {
     pub might: u16,
     pub speed: u16,
@@ -197,7 +201,7 @@ pub const BASE_ATTRIBUTES_SIZE: usize =
     16; // rate
 
 /// The struct for character attributes.
-#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+#[derive(Clone, Default, AnchorSerialize, AnchorDeserialize)]
 pub struct BaseAttributes {
     pub atk: u16,
     pub def: u16,
@@ -210,6 +214,7 @@ pub struct BaseAttributes {
 pub enum ResourceType {
     Raw = 1,
     Basic,
+    Item,
 }
 
 /// The size of a craft and forge recipe.
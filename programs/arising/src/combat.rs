@@ -0,0 +1,220 @@
+use crate::codex::*;
+use crate::random::*;
+
+/// Maximum amount of rounds before a fight is considered lost.
+pub const MAX_ROUNDS: u16 = 50;
+
+const BASE_HEALTH: u32 = 20;
+const HEALTH_PER_MIGHT: u32 = 5;
+const HEALTH_PER_LEVEL: u32 = 3;
+
+/// A participant of a fight, either the character or the mob.
+#[derive(Clone)]
+pub struct Combatant {
+    pub level: u16,
+    pub stats: BaseStats,
+    pub attributes: BaseAttributes,
+}
+
+impl Combatant {
+    /// The health points the combatant starts the fight with.
+    pub fn health(&self) -> u32 {
+        return BASE_HEALTH +
+            (self.stats.might as u32) * HEALTH_PER_MIGHT +
+            (self.level as u32) * HEALTH_PER_LEVEL;
+    }
+
+    /// The combatant with the highest initiative strikes first.
+    pub fn initiative(&self) -> u32 {
+        return (self.stats.speed as u32) +
+            (self.attributes.rate as u32) +
+            (self.attributes.range as u32);
+    }
+
+    /// The damage dealt on every hit, the best of the physical and magical attacks.
+    pub fn damage_against(&self, target: &Combatant) -> u32 {
+        let physical = ((self.attributes.atk as u32) + (self.stats.might as u32)).saturating_sub(
+            target.attributes.def as u32
+        );
+
+        let magical = (
+            (self.attributes.mag_atk as u32) + (self.stats.intellect as u32)
+        ).saturating_sub(target.attributes.mag_def as u32);
+
+        return physical.max(magical).max(1);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FightOutcome {
+    Win,
+    Loss,
+}
+
+/// The result of a fight between a character and a mob.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FightResult {
+    pub outcome: FightOutcome,
+    pub rounds: u16,
+    pub character_health: u32,
+    pub mob_health: u32,
+}
+
+/// Rolls the damage of a single hit, up to half of the base damage on top of it.
+#[inline(always)]
+fn roll_damage(damage: u32, rng: &mut Rng) -> u32 {
+    return damage + (rng.roll((damage / 2 + 1) as u64) as u32);
+}
+
+/// Resolves a turn based fight between the character and a mob.
+/// Both combatants hit each other once per round, the one with the highest initiative first
+/// (ties go to the character). The character loses if the mob is still alive after `MAX_ROUNDS`.
+pub fn fight(character: &Combatant, mob: &Combatant, rng: &mut Rng) -> FightResult {
+    let mut character_health = character.health();
+    let mut mob_health = mob.health();
+
+    let character_damage = character.damage_against(mob);
+    let mob_damage = mob.damage_against(character);
+
+    let character_first = character.initiative() >= mob.initiative();
+
+    let mut rounds: u16 = 0;
+
+    loop {
+        if rounds >= MAX_ROUNDS {
+            break;
+        }
+
+        rounds += 1;
+
+        if character_first {
+            mob_health = mob_health.saturating_sub(roll_damage(character_damage, rng));
+            if mob_health == 0 {
+                break;
+            }
+
+            character_health = character_health.saturating_sub(roll_damage(mob_damage, rng));
+            if character_health == 0 {
+                break;
+            }
+        } else {
+            character_health = character_health.saturating_sub(roll_damage(mob_damage, rng));
+            if character_health == 0 {
+                break;
+            }
+
+            mob_health = mob_health.saturating_sub(roll_damage(character_damage, rng));
+            if mob_health == 0 {
+                break;
+            }
+        }
+    }
+
+    let outcome = if mob_health == 0 { FightOutcome::Win } else { FightOutcome::Loss };
+
+    return FightResult {
+        outcome,
+        rounds,
+        character_health,
+        mob_health,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rng() -> Rng {
+        return Rng::new(&[9u8; 32]);
+    }
+
+    fn combatant(level: u16, stats: (u16, u16, u16), atk: u16, def: u16) -> Combatant {
+        return Combatant {
+            level,
+            stats: BaseStats { might: stats.0, speed: stats.1, intellect: stats.2 },
+            attributes: BaseAttributes {
+                atk,
+                def,
+                range: 0,
+                mag_atk: 0,
+                mag_def: 0,
+                rate: 0,
+            },
+        };
+    }
+
+    #[test]
+    fn stronger_character_wins() {
+        let character = combatant(5, (4, 3, 2), 2, 1);
+        let mob = combatant(0, (1, 1, 0), 1, 1);
+
+        let result = fight(&character, &mob, &mut rng());
+
+        assert_eq!(result.outcome, FightOutcome::Win);
+        assert_eq!(result.mob_health, 0);
+        assert!(result.character_health > 0);
+    }
+
+    #[test]
+    fn weaker_character_loses() {
+        let character = combatant(0, (1, 1, 1), 0, 0);
+        let mob = combatant(10, (10, 5, 0), 5, 5);
+
+        let result = fight(&character, &mob, &mut rng());
+
+        assert_eq!(result.outcome, FightOutcome::Loss);
+        assert_eq!(result.character_health, 0);
+    }
+
+    #[test]
+    fn initiative_decides_a_mirror_fight() {
+        let character = combatant(1, (2, 2, 2), 1000, 0);
+        let mob = combatant(1, (2, 2, 2), 1000, 0);
+
+        // Both sides kill with a single hit, ties go to the character.
+        assert_eq!(fight(&character, &mob, &mut rng()).outcome, FightOutcome::Win);
+
+        let faster_mob = combatant(1, (2, 3, 2), 1000, 0);
+        assert_eq!(fight(&character, &faster_mob, &mut rng()).outcome, FightOutcome::Loss);
+    }
+
+    #[test]
+    fn stalemate_is_a_loss() {
+        let character = combatant(150, (1000, 0, 0), 0, 1000);
+        let mob = character.clone();
+
+        let result = fight(&character, &mob, &mut rng());
+
+        assert_eq!(result.rounds, MAX_ROUNDS);
+        assert_eq!(result.outcome, FightOutcome::Loss);
+    }
+
+    #[test]
+    fn fights_are_deterministic() {
+        let character = combatant(3, (3, 2, 4), 1, 2);
+        let mob = combatant(2, (2, 2, 1), 2, 1);
+
+        assert_eq!(fight(&character, &mob, &mut rng()), fight(&character, &mob, &mut rng()));
+    }
+
+    #[test]
+    fn damage_rolls_stay_within_bounds() {
+        let mut rng = rng();
+
+        let mut i = 0;
+        while i < 100 {
+            let damage = roll_damage(10, &mut rng);
+            assert!((10..=15).contains(&damage));
+            i += 1;
+        }
+    }
+
+    #[test]
+    fn magic_damage_is_used_when_higher() {
+        let mut mage = combatant(0, (0, 0, 6), 0, 0);
+        mage.attributes.mag_atk = 2;
+        let target = combatant(0, (0, 0, 0), 0, 10);
+
+        assert_eq!(mage.damage_against(&target), 8);
+    }
+}
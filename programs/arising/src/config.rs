@@ -6,29 +6,135 @@ pub const CONFIG_PREFIX: &str = "arising_config_account";
 pub const CONFIG_ACCOUNT_SIZE: usize =
     8 + // discriminator
     1 + // paused
+    1 + // forge_paused
+    1 + // quests_paused
+    1 + // refresh_paused
     1 + // initialized
     32 + // authority
+    32 + // pending_authority
     64 + // seconds_between_refreshes
     64 + // seconds_between_paid_refreshes
     64 + // max_characters
+    64 + // characters
     64 + // experience_multiplier
+    64 + // experience_event_multiplier
+    64 + // experience_event_start
+    64 + // experience_event_end
     64 + // forge_recipes
     64 + // craft_recipes
-    64; // upgrade_recipes
+    64 + // upgrade_recipes
+    64 + // item_definitions
+    32 + // refresher_mint
+    32 + // character_collection
+    32 + // character_creator
+    32 + // respec_mint
+    64 + // respec_cost
+    64; // seconds_between_respecs
+
+/// The longest interval the authority can set between refreshes, 1 year.
+pub const MAX_SECONDS_BETWEEN_REFRESHES: u64 = 31_536_000;
+
+/// The longest cooldown the authority can set between respecs, 1 year.
+pub const MAX_SECONDS_BETWEEN_RESPECS: u64 = 31_536_000;
+
+/// The highest respec cost in base units of the respec token.
+pub const MAX_RESPEC_COST: u64 = 1_000_000_000_000;
 
 /// Arising program config settings.
 #[account]
 #[derive(Default)]
 pub struct Config {
     pub paused: bool,
+    pub forge_paused: bool,
+    pub quests_paused: bool,
+    pub refresh_paused: bool,
     pub initialized: bool,
     pub authority: Pubkey,
+    pub pending_authority: Pubkey,
     pub seconds_between_refreshes: u64,
     pub seconds_between_paid_refreshes: u64,
     pub max_characters: u64,
+    pub characters: u64,
     pub experience_multiplier: u64,
+    pub experience_event_multiplier: u64,
+    pub experience_event_start: u64,
+    pub experience_event_end: u64,
     pub forge_recipes: u64,
     pub craft_recipes: u64,
     pub upgrade_recipes: u64,
     pub quests: u64,
-}
\ No newline at end of file
+    pub item_definitions: u64,
+    pub refresher_mint: Pubkey,
+    pub character_collection: Pubkey,
+    pub character_creator: Pubkey,
+    pub respec_mint: Pubkey,
+    pub respec_cost: u64,
+    pub seconds_between_respecs: u64,
+}
+
+/// Parts of the game that can be paused on their own.
+pub enum Subsystem {
+    Game = 0,
+    Forge,
+    Quests,
+    Refresh,
+}
+
+/// Checks if the game or the given subsystem is paused.
+pub fn is_paused(config: &Config, subsystem: Subsystem) -> bool {
+    if config.paused {
+        return true;
+    }
+
+    return match subsystem {
+        Subsystem::Game => false,
+        Subsystem::Forge => config.forge_paused,
+        Subsystem::Quests => config.quests_paused,
+        Subsystem::Refresh => config.refresh_paused,
+    };
+}
+
+/// Emitted when a proposed authority accepts the program authority.
+#[event]
+pub struct AuthorityTransferred {
+    pub previous_authority: Pubkey,
+    pub authority: Pubkey,
+}
+
+/// Emitted when the authority changes the config timing or caps.
+#[event]
+pub struct ConfigUpdated {
+    pub seconds_between_refreshes: u64,
+    pub seconds_between_paid_refreshes: u64,
+    pub max_characters: u64,
+}
+
+/// The experience multiplier at the given time, an active event replaces the base multiplier.
+pub fn experience_multiplier(config: &Config, now: u64) -> u64 {
+    if now >= config.experience_event_start && now < config.experience_event_end {
+        return config.experience_event_multiplier;
+    }
+
+    return config.experience_multiplier;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_only_apply_in_their_window() {
+        let config = Config {
+            experience_multiplier: 1,
+            experience_event_multiplier: 2,
+            experience_event_start: 100,
+            experience_event_end: 200,
+            ..Config::default()
+        };
+
+        assert_eq!(experience_multiplier(&config, 99), 1);
+        assert_eq!(experience_multiplier(&config, 100), 2);
+        assert_eq!(experience_multiplier(&config, 199), 2);
+        assert_eq!(experience_multiplier(&config, 200), 1);
+    }
+}
@@ -0,0 +1,250 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{ self, CreateMetadataAccountsV3, Metadata },
+    token::{ self, spl_token::instruction::AuthorityType, Mint, MintTo, SetAuthority, Token, TokenAccount },
+};
+use mpl_token_metadata::state::DataV2;
+
+use crate::characters::*;
+use crate::codex::*;
+use crate::config::*;
+use crate::errors::*;
+use crate::items::*;
+use crate::materials::*;
+use crate::operators::*;
+
+const CRAFT_RECIPE_PREFIX: &str = "arising_craft_recipe";
+
+#[inline(always)]
+pub fn is_valid_craft_materials(recipe: &Recipe) -> bool {
+    return are_valid_materials(&recipe.materials, &recipe.materials_types);
+}
+
+#[inline(always)]
+pub fn is_valid_craft_reward(recipe: &Recipe) -> bool {
+    return recipe.item_rewarded != 0 &&
+        recipe.item_rewarded_type == (ResourceType::Item as u16) &&
+        recipe.item_rewarded_amount == 1;
+}
+
+/// Checks the rewarded item has a definition to mint it from, definition ids are sequential.
+#[inline(always)]
+pub fn is_defined_craft_reward(recipe: &Recipe, config: &Config) -> bool {
+    return (recipe.item_rewarded as u64) <= config.item_definitions;
+}
+
+/// Mints the crafted item token to the payer with its metadata,
+/// then revokes the mint authority so the supply stays at 1.
+pub fn mint_item(accounts: &ClaimCraft, bump: u8) -> Result<()> {
+    let seeds: &[&[u8]] = &[ITEM_AUTHORITY_PREFIX.as_bytes(), &[bump]];
+    let signer = &[seeds];
+
+    let token_program = accounts.token_program.to_account_info();
+    let item_authority = accounts.item_authority.to_account_info();
+
+    let mint_to = MintTo {
+        mint: accounts.item_mint.to_account_info(),
+        to: accounts.item_token_account.to_account_info(),
+        authority: item_authority.clone(),
+    };
+
+    token::mint_to(CpiContext::new_with_signer(token_program.clone(), mint_to, signer), 1)?;
+
+    let create_metadata = CreateMetadataAccountsV3 {
+        metadata: accounts.item_metadata.to_account_info(),
+        mint: accounts.item_mint.to_account_info(),
+        mint_authority: item_authority.clone(),
+        payer: accounts.payer.to_account_info(),
+        update_authority: item_authority.clone(),
+        system_program: accounts.system_program.to_account_info(),
+        rent: accounts.rent.to_account_info(),
+    };
+
+    let data = DataV2 {
+        name: accounts.item_definition.name.clone(),
+        symbol: ITEM_SYMBOL.to_string(),
+        uri: accounts.item_definition.uri.clone(),
+        seller_fee_basis_points: 0,
+        creators: None,
+        collection: None,
+        uses: None,
+    };
+
+    metadata::create_metadata_accounts_v3(
+        CpiContext::new_with_signer(
+            accounts.token_metadata_program.to_account_info(),
+            create_metadata,
+            signer
+        ),
+        data,
+        true,
+        true,
+        None
+    )?;
+
+    let set_authority = SetAuthority {
+        current_authority: item_authority,
+        account_or_mint: accounts.item_mint.to_account_info(),
+    };
+
+    return token::set_authority(
+        CpiContext::new_with_signer(token_program, set_authority, signer),
+        AuthorityType::MintTokens,
+        None
+    );
+}
+
+#[derive(Accounts)]
+pub struct CraftAccess<'info> {
+    #[account(mut,
+        constraint = is_mint_owner(character.mint, payer.key(), &character_token_account) @ ArisingError::InvalidOwner)]
+    payer: Signer<'info>,
+
+    #[account(mut,
+        seeds = [CHARACTER_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character: Account<'info, Character>,
+
+    #[account(mut,
+        seeds = [CHARACTER_MATERIAL_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character_materials: Account<'info, CharacterMaterials>,
+
+    #[account(mut,
+        seeds = [CHARACTER_SLOTS_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character_slots: Account<'info, CharacterSlots>,
+
+    #[account(mut)]
+    pub character_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = !is_paused(&config, Subsystem::Game) @ ArisingError::Paused)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub craft_recipe: Account<'info, CraftRecipe>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimCraft<'info> {
+    #[account(mut,
+        constraint = is_mint_owner(character.mint, payer.key(), &character_token_account) @ ArisingError::InvalidOwner)]
+    payer: Signer<'info>,
+
+    #[account(mut,
+        seeds = [CHARACTER_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character: Account<'info, Character>,
+
+    #[account(mut,
+        seeds = [CHARACTER_SLOTS_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character_slots: Account<'info, CharacterSlots>,
+
+    #[account(mut)]
+    pub character_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = !is_paused(&config, Subsystem::Game) @ ArisingError::Paused)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub craft_recipe: Account<'info, CraftRecipe>,
+
+    #[account(
+        seeds = [ITEM_DEFINITION_PREFIX.as_bytes(), &craft_recipe.recipe.item_rewarded.to_le_bytes()],
+        bump)]
+    pub item_definition: Account<'info, ItemDefinition>,
+
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = 0,
+        mint::authority = item_authority,
+        mint::freeze_authority = item_authority
+    )]
+    pub item_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = item_mint,
+        associated_token::authority = payer
+    )]
+    pub item_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [ITEM_PREFIX.as_bytes(), &item_mint.key().to_bytes()],
+        bump,
+        space = ITEM_INSTANCE_SIZE
+    )]
+    pub item: Account<'info, ItemInstance>,
+
+    /// CHECK: the PDA signing as the mint, freeze and metadata update authority of the items.
+    #[account(seeds = [ITEM_AUTHORITY_PREFIX.as_bytes()], bump)]
+    pub item_authority: UncheckedAccount<'info>,
+
+    /// CHECK: the metadata address is derived and checked by the token metadata program.
+    #[account(mut)]
+    pub item_metadata: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCraftRecipe<'info> {
+    #[account(
+        constraint = is_authorized(&config, authority.key(), &operator, Role::Content) @ ArisingError::InvalidAuthority)]
+    authority: Signer<'info>,
+
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+
+    pub operator: Option<Account<'info, Operator>>,
+
+    #[account(mut)]
+    pub craft_recipe: Account<'info, CraftRecipe>,
+}
+
+#[derive(Accounts)]
+#[instruction(bump: u8, id: u32)]
+pub struct AddCraftRecipe<'info> {
+    #[account(mut,
+        constraint = (config.craft_recipes + 1) == (id as u64) @ CraftError::InvalidID
+    )]
+    payer: Signer<'info>,
+
+    #[account(
+        constraint = is_authorized(&config, authority.key(), &operator, Role::Content) @ ArisingError::InvalidAuthority)]
+    authority: Signer<'info>,
+
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+
+    pub operator: Option<Account<'info, Operator>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [CRAFT_RECIPE_PREFIX.as_bytes(), &id.to_le_bytes()],
+        bump,
+        space = RECIPE_SIZE
+    )]
+    pub craft_recipe: Account<'info, CraftRecipe>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Craft recipes account storage
+#[account]
+pub struct CraftRecipe {
+    pub recipe: Recipe,
+}
@@ -23,6 +23,30 @@ pub enum CharacterError {
     NotAbleToClaimQuest,
     #[msg("Character: not enough resources to perform the task")]
     NotEnoughResources,
+    #[msg("Character: character not able to craft a recipe now")]
+    NotAbleToCraftRecipe,
+    #[msg("Character: character not able to claim the craft recipe now")]
+    NotAbleToClaimCraftRecipe,
+    #[msg("Character: token is not the refresher token")]
+    InvalidRefresherMint,
+    #[msg("Character: not enough refresher tokens to burn")]
+    NotEnoughRefresherTokens,
+    #[msg("Character: character not able to upgrade an item now")]
+    NotAbleToUpgradeItem,
+    #[msg("Character: character not able to claim the upgrade now")]
+    NotAbleToClaimUpgrade,
+    #[msg("Character: not able to respec now")]
+    RespecNotAvailable,
+    #[msg("Character: token is not the respec token")]
+    InvalidRespecMint,
+    #[msg("Character: not enough respec tokens to burn")]
+    NotEnoughRespecTokens,
+    #[msg("Character: mint is not a character NFT")]
+    InvalidCharacterMint,
+    #[msg("Character: NFT is not verified in the Arising collection")]
+    InvalidCharacterCollection,
+    #[msg("Character: NFT is not verified by the Arising creator")]
+    InvalidCharacterCreator,
 }
 
 /// Forge context errors
@@ -32,6 +56,82 @@ pub enum ForgeError {
     NotAvailable,
     #[msg("Forge: invalid forge recipe ID.")]
     InvalidID,
+    #[msg("Forge: invalid material or material type.")]
+    InvalidMaterial,
+}
+
+/// Craft context errors
+#[error_code]
+pub enum CraftError {
+    #[msg("Craft: recipe is not available.")]
+    NotAvailable,
+    #[msg("Craft: invalid craft recipe ID.")]
+    InvalidID,
+    #[msg("Craft: recipe must reward a single item.")]
+    InvalidReward,
+    #[msg("Craft: invalid material or material type.")]
+    InvalidMaterial,
+}
+
+/// Item context errors
+#[error_code]
+pub enum ItemError {
+    #[msg("Item: invalid item definition ID.")]
+    InvalidID,
+    #[msg("Item: invalid item type.")]
+    InvalidType,
+    #[msg("Item: invalid equipment slot for the item.")]
+    InvalidSlot,
+    #[msg("Item: equipment slot is already occupied.")]
+    SlotOccupied,
+    #[msg("Item: two handed items need both hands free.")]
+    HandsOccupied,
+    #[msg("Item: item is locked by another action.")]
+    ItemLocked,
+    #[msg("Item: item is not equipped in the slot.")]
+    ItemNotEquipped,
+    #[msg("Item: accounts don't match the equipped items.")]
+    InvalidEquippedAccounts,
+    #[msg("Item: payer is not owner of the item token.")]
+    NotItemOwner,
+}
+
+/// Upgrade context errors
+#[error_code]
+pub enum UpgradeError {
+    #[msg("Upgrade: recipe is not available.")]
+    NotAvailable,
+    #[msg("Upgrade: invalid upgrade recipe ID.")]
+    InvalidID,
+    #[msg("Upgrade: recipe doesn't apply to the item.")]
+    InvalidItem,
+    #[msg("Upgrade: item is not being upgraded.")]
+    ItemNotUpgrading,
+    #[msg("Upgrade: success rate is above the precision.")]
+    InvalidSuccessRate,
+    #[msg("Upgrade: revealed value doesn't match the commitment.")]
+    InvalidReveal,
+    #[msg("Upgrade: no slot hash available after the commitment yet.")]
+    EntropyNotAvailable,
+    #[msg("Upgrade: invalid material or material type.")]
+    InvalidMaterial,
+    #[msg("Upgrade: the slot hash after the commitment aged out.")]
+    EntropyExpired,
+    #[msg("Upgrade: item is not the one locked when the upgrade started.")]
+    InvalidLockedItem,
+}
+
+/// Material context errors
+#[error_code]
+pub enum MaterialError {
+    #[msg("Material: invalid material type or ID.")]
+    InvalidMaterial,
+    #[msg("Material: token is not the material mint.")]
+    InvalidMint,
+    #[msg("Material: amount must be greater than 0.")]
+    InvalidAmount,
+    #[msg("Material: can't transfer materials to the same character.")]
+    SameCharacter,
 }
 
 /// Quest context errors
@@ -41,6 +141,14 @@ pub enum QuestError {
     NotAvailable,
     #[msg("Quest: invalid quest ID.")]
     InvalidID,
+    #[msg("Quest: revealed value doesn't match the commitment.")]
+    InvalidReveal,
+    #[msg("Quest: no slot hash available after the commitment yet.")]
+    EntropyNotAvailable,
+    #[msg("Quest: invalid material.")]
+    InvalidMaterial,
+    #[msg("Quest: the slot hash after the commitment aged out.")]
+    EntropyExpired,
 }
 
 /// Program context errors.
@@ -50,4 +158,32 @@ pub enum ArisingError {
     InvalidAuthority,
     #[msg("Arising: payer is not owner of the token.")]
     InvalidOwner,
+    #[msg("Arising: math operation overflowed.")]
+    MathOverflow,
+    #[msg("Arising: math operation underflowed.")]
+    MathUnderflow,
+    #[msg("Arising: experience multiplier must be greater than 0.")]
+    InvalidExperienceMultiplier,
+    #[msg("Arising: experience event must end after it starts.")]
+    InvalidExperienceEvent,
+    #[msg("Arising: refresh intervals must be greater than 0 and up to the max interval.")]
+    InvalidRefreshInterval,
+    #[msg("Arising: max characters can't be below the current characters.")]
+    InvalidMaxCharacters,
+    #[msg("Arising: the max characters were already added.")]
+    CharacterCapReached,
+    #[msg("Arising: the game or this feature is paused.")]
+    Paused,
+    #[msg("Arising: invalid subsystem to pause.")]
+    InvalidSubsystem,
+    #[msg("Arising: signer is not the proposed authority.")]
+    InvalidPendingAuthority,
+    #[msg("Arising: unknown operator roles.")]
+    InvalidRoles,
+    #[msg("Arising: respec cost is above the max cost.")]
+    InvalidRespecCost,
+    #[msg("Arising: seconds between respecs is above the max cooldown.")]
+    InvalidRespecInterval,
+    #[msg("Arising: no pending task has a slot hash to store.")]
+    SlotHashNotStored,
 }
\ No newline at end of file
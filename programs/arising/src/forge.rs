@@ -5,29 +5,41 @@ use crate::characters::*;
 use crate::codex::*;
 use crate::config::*;
 use crate::errors::*;
+use crate::materials::*;
+use crate::math::*;
+use crate::operators::*;
 
 const FORGE_RECIPE_PREFIX: &str = "arising_forge_recipe";
 
+/// Checks the recipe consumes and rewards known raw or basic materials.
+#[inline(always)]
+pub fn is_valid_forge_recipe(recipe: &Recipe) -> bool {
+    return are_valid_materials(&recipe.materials, &recipe.materials_types) &&
+        is_valid_material(recipe.item_rewarded_type, recipe.item_rewarded);
+}
+
 #[inline(always)]
 pub fn forge_reward(
-    character: &mut Account<Character>,
+    character_materials: &mut Account<CharacterMaterials>,
     material: u32,
     amount: u32,
     material_type: u16,
-) {
+) -> Result<()> {
     if material == 0 {
-        return;
+        return Ok(());
     }
 
+    let index = (material as usize) - 1;
+
     if material_type == (ResourceType::Basic as u16) {
-        character.basic[(material as usize) - 1] += amount;
+        character_materials.basic[index] = checked_add(character_materials.basic[index], amount)?;
     }
 
     if material_type == (ResourceType::Raw as u16) {
-        character.raw[(material as usize) - 1] += amount;
+        character_materials.raw[index] = checked_add(character_materials.raw[index], amount)?;
     }
 
-    return;
+    return Ok(());
 }
 
 #[derive(Accounts)]
@@ -36,25 +48,43 @@ pub struct ForgeAccess<'info> {
         constraint = is_mint_owner(character.mint, payer.key(), &character_token_account) @ ArisingError::InvalidOwner)]
     payer: Signer<'info>,
 
-    #[account(mut)]
+    #[account(mut,
+        seeds = [CHARACTER_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
     pub character: Account<'info, Character>,
 
+    #[account(mut,
+        seeds = [CHARACTER_MATERIAL_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character_materials: Account<'info, CharacterMaterials>,
+
+    #[account(mut,
+        seeds = [CHARACTER_SLOTS_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character_slots: Account<'info, CharacterSlots>,
+
     #[account(mut)]
     pub character_token_account: Account<'info, TokenAccount>,
 
+    #[account(
+        constraint = !is_paused(&config, Subsystem::Forge) @ ArisingError::Paused)]
+    pub config: Account<'info, Config>,
+
     #[account(mut)]
     pub forge_recipe: Account<'info, ForgeRecipe>,
 }
 
 #[derive(Accounts)]
 pub struct UpdateForgeRecipe<'info> {
-    #[account(mut,
-        constraint = payer.key() == config.authority @ ArisingError::InvalidAuthority)]
-    payer: Signer<'info>,
+    #[account(
+        constraint = is_authorized(&config, authority.key(), &operator, Role::Content) @ ArisingError::InvalidAuthority)]
+    authority: Signer<'info>,
 
     #[account(mut)]
     pub config: Account<'info, Config>,
 
+    pub operator: Option<Account<'info, Operator>>,
+
     #[account(mut)]
     pub forge_recipe: Account<'info, ForgeRecipe>,
 }
@@ -63,14 +93,19 @@ pub struct UpdateForgeRecipe<'info> {
 #[instruction(bump: u8, id: u32)]
 pub struct AddForgeRecipe<'info> {
     #[account(mut,
-        constraint = payer.key() == config.authority @ ArisingError::InvalidAuthority,
-        constraint = (config.forge_recipes + 1) == id.into() @ ForgeError::InvalidID
+        constraint = (config.forge_recipes + 1) == (id as u64) @ ForgeError::InvalidID
     )]
     payer: Signer<'info>,
 
+    #[account(
+        constraint = is_authorized(&config, authority.key(), &operator, Role::Content) @ ArisingError::InvalidAuthority)]
+    authority: Signer<'info>,
+
     #[account(mut)]
     pub config: Account<'info, Config>,
 
+    pub operator: Option<Account<'info, Operator>>,
+
     #[account(
         init,
         payer = payer,
@@ -0,0 +1,377 @@
+use anchor_lang::prelude::*;
+
+use anchor_spl::token::{ self, FreezeAccount, Mint, ThawAccount, Token, TokenAccount };
+
+use crate::characters::*;
+use crate::codex::*;
+use crate::config::*;
+use crate::errors::*;
+use crate::operators::*;
+
+pub const ITEM_PREFIX: &str = "arising_item";
+pub const ITEM_DEFINITION_PREFIX: &str = "arising_item_definition";
+pub const ITEM_AUTHORITY_PREFIX: &str = "arising_item_authority";
+
+/// The symbol of every item token metadata.
+pub const ITEM_SYMBOL: &str = "ARISING";
+
+/// The durability of a newly crafted item.
+pub const MAX_DURABILITY: u16 = 100;
+
+/// The equipment an item fits in, matching `ITEM_TYPE` on the client.
+pub enum ItemType {
+    OneHanded = 1,
+    TwoHanded,
+    Helmet,
+    ShoulderGuard,
+    ArmGuard,
+    Hand,
+    Ring,
+    Necklace,
+    Chest,
+    Leg,
+    Belt,
+    Feet,
+    Cape,
+}
+
+#[inline(always)]
+pub fn is_valid_item_type(item_type: u16) -> bool {
+    return item_type >= (ItemType::OneHanded as u16) && item_type <= (ItemType::Cape as u16);
+}
+
+/// The character equipment slots, in the `CharacterEquipment` order.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum EquipmentSlot {
+    Helmet = 1,
+    ShoulderGuards,
+    ArmGuards,
+    Hands,
+    Rings,
+    Necklace,
+    Chest,
+    Legs,
+    Belt,
+    Feet,
+    Cape,
+    LeftHand,
+    RightHand,
+}
+
+/// Returns the equipment slot matching the given number.
+pub fn equipment_slot(slot: u16) -> Option<EquipmentSlot> {
+    return match slot {
+        1 => Some(EquipmentSlot::Helmet),
+        2 => Some(EquipmentSlot::ShoulderGuards),
+        3 => Some(EquipmentSlot::ArmGuards),
+        4 => Some(EquipmentSlot::Hands),
+        5 => Some(EquipmentSlot::Rings),
+        6 => Some(EquipmentSlot::Necklace),
+        7 => Some(EquipmentSlot::Chest),
+        8 => Some(EquipmentSlot::Legs),
+        9 => Some(EquipmentSlot::Belt),
+        10 => Some(EquipmentSlot::Feet),
+        11 => Some(EquipmentSlot::Cape),
+        12 => Some(EquipmentSlot::LeftHand),
+        13 => Some(EquipmentSlot::RightHand),
+        _ => None,
+    };
+}
+
+/// Checks the item type can be equipped in the slot, weapons go in either hand.
+pub fn fits_slot(item_type: u16, slot: EquipmentSlot) -> bool {
+    if
+        item_type == (ItemType::OneHanded as u16) ||
+        item_type == (ItemType::TwoHanded as u16)
+    {
+        return slot == EquipmentSlot::LeftHand || slot == EquipmentSlot::RightHand;
+    }
+
+    let expected = match item_type {
+        3 => EquipmentSlot::Helmet,
+        4 => EquipmentSlot::ShoulderGuards,
+        5 => EquipmentSlot::ArmGuards,
+        6 => EquipmentSlot::Hands,
+        7 => EquipmentSlot::Rings,
+        8 => EquipmentSlot::Necklace,
+        9 => EquipmentSlot::Chest,
+        10 => EquipmentSlot::Legs,
+        11 => EquipmentSlot::Belt,
+        12 => EquipmentSlot::Feet,
+        13 => EquipmentSlot::Cape,
+        _ => {
+            return false;
+        }
+    };
+
+    return expected == slot;
+}
+
+/// Returns the item stored in the equipment slot, `Pubkey::default()` when empty.
+pub fn equipped_item(equipment: &mut CharacterEquipment, slot: EquipmentSlot) -> &mut Pubkey {
+    return match slot {
+        EquipmentSlot::Helmet => &mut equipment.helmet,
+        EquipmentSlot::ShoulderGuards => &mut equipment.shoulder_guards,
+        EquipmentSlot::ArmGuards => &mut equipment.arm_guards,
+        EquipmentSlot::Hands => &mut equipment.hands,
+        EquipmentSlot::Rings => &mut equipment.rings,
+        EquipmentSlot::Necklace => &mut equipment.necklace,
+        EquipmentSlot::Chest => &mut equipment.chest,
+        EquipmentSlot::Legs => &mut equipment.legs,
+        EquipmentSlot::Belt => &mut equipment.belt,
+        EquipmentSlot::Feet => &mut equipment.feet,
+        EquipmentSlot::Cape => &mut equipment.cape,
+        EquipmentSlot::LeftHand => &mut equipment.left_hand,
+        EquipmentSlot::RightHand => &mut equipment.right_hand,
+    };
+}
+
+/// Clears every equipment slot holding the item, a two handed item holds both hands.
+pub fn clear_equipped(equipment: &mut CharacterEquipment, item: Pubkey) {
+    let mut slot = EquipmentSlot::Helmet as u16;
+
+    loop {
+        if slot > (EquipmentSlot::RightHand as u16) {
+            break;
+        }
+
+        if let Some(equipment_slot) = equipment_slot(slot) {
+            let equipped = equipped_item(equipment, equipment_slot);
+
+            if *equipped == item {
+                *equipped = Pubkey::default();
+            }
+        }
+
+        slot += 1;
+    }
+}
+
+#[inline(always)]
+pub fn other_hand(slot: EquipmentSlot) -> EquipmentSlot {
+    if slot == EquipmentSlot::LeftHand {
+        return EquipmentSlot::RightHand;
+    }
+
+    return EquipmentSlot::LeftHand;
+}
+
+/// Freezes the item token account so the item can't be moved while it is in use.
+pub fn freeze_item<'info>(
+    token_program: AccountInfo<'info>,
+    item_token_account: AccountInfo<'info>,
+    item_mint: AccountInfo<'info>,
+    item_authority: AccountInfo<'info>,
+    bump: u8
+) -> Result<()> {
+    let seeds: &[&[u8]] = &[ITEM_AUTHORITY_PREFIX.as_bytes(), &[bump]];
+    let signer = &[seeds];
+
+    let accounts = FreezeAccount {
+        account: item_token_account,
+        mint: item_mint,
+        authority: item_authority,
+    };
+
+    return token::freeze_account(CpiContext::new_with_signer(token_program, accounts, signer));
+}
+
+/// Thaws the item token account once the item is free again.
+pub fn thaw_item<'info>(
+    token_program: AccountInfo<'info>,
+    item_token_account: AccountInfo<'info>,
+    item_mint: AccountInfo<'info>,
+    item_authority: AccountInfo<'info>,
+    bump: u8
+) -> Result<()> {
+    let seeds: &[&[u8]] = &[ITEM_AUTHORITY_PREFIX.as_bytes(), &[bump]];
+    let signer = &[seeds];
+
+    let accounts = ThawAccount {
+        account: item_token_account,
+        mint: item_mint,
+        authority: item_authority,
+    };
+
+    return token::thaw_account(CpiContext::new_with_signer(token_program, accounts, signer));
+}
+
+#[derive(Accounts)]
+pub struct EquipmentAccess<'info> {
+    #[account(mut,
+        constraint = is_mint_owner(character.mint, payer.key(), &character_token_account) @ ArisingError::InvalidOwner)]
+    payer: Signer<'info>,
+
+    #[account(
+        seeds = [CHARACTER_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character: Account<'info, Character>,
+
+    #[account(mut,
+        seeds = [CHARACTER_EQUIPMENT_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character_equipment: Account<'info, CharacterEquipment>,
+
+    pub character_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = !is_paused(&config, Subsystem::Game) @ ArisingError::Paused)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut,
+        seeds = [ITEM_PREFIX.as_bytes(), &item.mint.to_bytes()],
+        bump)]
+    pub item: Account<'info, ItemInstance>,
+
+    #[account(
+        seeds = [ITEM_DEFINITION_PREFIX.as_bytes(), &item.id.to_le_bytes()],
+        bump)]
+    pub item_definition: Account<'info, ItemDefinition>,
+
+    #[account(address = item.mint)]
+    pub item_mint: Account<'info, Mint>,
+
+    #[account(mut,
+        constraint = is_mint_owner(item.mint, payer.key(), &item_token_account) @ ItemError::NotItemOwner)]
+    pub item_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: the PDA signing as the freeze authority of the item mints.
+    #[account(seeds = [ITEM_AUTHORITY_PREFIX.as_bytes()], bump)]
+    pub item_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Unequipping only needs the item owner, the character may have changed hands since the item
+/// was equipped and frozen in the owner wallet.
+#[derive(Accounts)]
+pub struct UnequipAccess<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    #[account(
+        seeds = [CHARACTER_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character: Account<'info, Character>,
+
+    #[account(mut,
+        seeds = [CHARACTER_EQUIPMENT_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character_equipment: Account<'info, CharacterEquipment>,
+
+    #[account(
+        constraint = !is_paused(&config, Subsystem::Game) @ ArisingError::Paused)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut,
+        seeds = [ITEM_PREFIX.as_bytes(), &item.mint.to_bytes()],
+        bump)]
+    pub item: Account<'info, ItemInstance>,
+
+    #[account(address = item.mint)]
+    pub item_mint: Account<'info, Mint>,
+
+    #[account(mut,
+        constraint = is_mint_owner(item.mint, payer.key(), &item_token_account) @ ItemError::NotItemOwner)]
+    pub item_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: the PDA signing as the freeze authority of the item mints.
+    #[account(seeds = [ITEM_AUTHORITY_PREFIX.as_bytes()], bump)]
+    pub item_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateItemDefinition<'info> {
+    #[account(
+        constraint = is_authorized(&config, authority.key(), &operator, Role::Content) @ ArisingError::InvalidAuthority)]
+    authority: Signer<'info>,
+
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+
+    pub operator: Option<Account<'info, Operator>>,
+
+    #[account(mut)]
+    pub item_definition: Account<'info, ItemDefinition>,
+}
+
+#[derive(Accounts)]
+#[instruction(bump: u8, id: u32)]
+pub struct AddItemDefinition<'info> {
+    #[account(mut,
+        constraint = (config.item_definitions + 1) == (id as u64) @ ItemError::InvalidID
+    )]
+    payer: Signer<'info>,
+
+    #[account(
+        constraint = is_authorized(&config, authority.key(), &operator, Role::Content) @ ArisingError::InvalidAuthority)]
+    authority: Signer<'info>,
+
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+
+    pub operator: Option<Account<'info, Operator>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [ITEM_DEFINITION_PREFIX.as_bytes(), &id.to_le_bytes()],
+        bump,
+        space = ITEM_DEFINITION_SIZE
+    )]
+    pub item_definition: Account<'info, ItemDefinition>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// The size of an item definition.
+pub const ITEM_DEFINITION_SIZE: usize = 8 + // discriminator
+    32 + // id
+    24 + // name
+    200 + // uri
+    16 + // item_type
+    BASE_STATS_SIZE + // stats_additions
+    BASE_ATTRIBUTES_SIZE + // attributes_additions
+    BASE_STATS_SIZE + // stats_reductions
+    BASE_ATTRIBUTES_SIZE + // attributes_reductions
+    16; // level_required
+
+/// The authoritative stats of an item, every crafted item points to a definition by id.
+#[account]
+pub struct ItemDefinition {
+    pub id: u32,
+    pub name: String,
+    pub uri: String,
+    pub item_type: u16,
+    pub stats_additions: BaseStats,
+    pub attributes_additions: BaseAttributes,
+    pub stats_reductions: BaseStats,
+    pub attributes_reductions: BaseAttributes,
+    pub level_required: u16,
+}
+
+/// The size of an item instance.
+pub const ITEM_INSTANCE_SIZE: usize = 8 + // discriminator
+    32 + // id
+    32 + // mint
+    16 + // tier
+    16 + // durability
+    BASE_STATS_SIZE + // stats_bonus
+    BASE_ATTRIBUTES_SIZE + // attributes_bonus
+    1 + // upgrading
+    1; // equipped
+
+/// The state of a crafted item token, the id is the item definition id.
+#[account]
+pub struct ItemInstance {
+    pub id: u32,
+    pub mint: Pubkey,
+    pub tier: u16,
+    pub durability: u16,
+    pub stats_bonus: BaseStats,
+    pub attributes_bonus: BaseAttributes,
+    pub upgrading: bool,
+    pub equipped: bool,
+}
@@ -1,19 +1,40 @@
+#![allow(clippy::needless_return, clippy::result_large_err)]
+
 mod config;
 mod utils;
 mod characters;
+mod combat;
+mod craft;
 mod errors;
 mod codex;
 mod forge;
+mod items;
+mod math;
+mod materials;
+mod operators;
 mod quests;
-
-use anchor_lang::prelude::*;
-
-use config::*;
-use characters::*;
-use forge::*;
-use quests::*;
-use codex::*;
-use errors::*;
+mod random;
+mod stats;
+mod upgrade;
+
+use anchor_lang::{ prelude::*, solana_program::program::set_return_data };
+use anchor_spl::token::Mint;
+
+pub use config::*;
+pub use characters::*;
+use combat::*;
+use math::*;
+pub use forge::*;
+pub use craft::*;
+pub use items::*;
+pub use materials::*;
+pub use operators::*;
+pub use quests::*;
+pub use codex::*;
+use random::*;
+pub use stats::*;
+pub use upgrade::*;
+pub use errors::*;
 use utils::*;
 
 declare_id!("GT1koQQwD6ZV6bxciNSwC3YFDHiByySKZbQ2MQJF4GWp");
@@ -26,14 +47,22 @@ pub mod arising {
         let config = &mut ctx.accounts.config;
         config.initialized = true;
         config.paused = true;
-        config.authority = ctx.accounts.authority.unsigned_key().clone();
+        config.forge_paused = false;
+        config.quests_paused = false;
+        config.refresh_paused = false;
+        config.authority = ctx.accounts.authority.key();
+        config.pending_authority = Pubkey::default();
         config.seconds_between_refreshes = 86_400; // 1 day
         config.seconds_between_paid_refreshes = 86_400; // 1 day
         config.experience_multiplier = 1;
         config.max_characters = 30_000;
+        config.characters = 0;
         config.forge_recipes = 0;
         config.craft_recipes = 0;
         config.upgrade_recipes = 0;
+        config.item_definitions = 0;
+        config.respec_cost = 1;
+        config.seconds_between_respecs = 604_800; // 1 week
 
         Ok(())
     }
@@ -46,28 +75,225 @@ pub mod arising {
         Ok(())
     }
 
+    pub fn set_subsystem_paused(ctx: Context<SetPause>, subsystem: u16, paused: bool) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        if subsystem == (Subsystem::Forge as u16) {
+            config.forge_paused = paused;
+        } else if subsystem == (Subsystem::Quests as u16) {
+            config.quests_paused = paused;
+        } else if subsystem == (Subsystem::Refresh as u16) {
+            config.refresh_paused = paused;
+        } else {
+            return Err(ArisingError::InvalidSubsystem.into());
+        }
+
+        msg!("Changing subsystem {} pause status to {}", subsystem, paused);
+
+        Ok(())
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        seconds_between_refreshes: Option<u64>,
+        seconds_between_paid_refreshes: Option<u64>,
+        max_characters: Option<u64>
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        if let Some(seconds) = seconds_between_refreshes {
+            if seconds == 0 || seconds > MAX_SECONDS_BETWEEN_REFRESHES {
+                return Err(ArisingError::InvalidRefreshInterval.into());
+            }
+
+            config.seconds_between_refreshes = seconds;
+        }
+
+        if let Some(seconds) = seconds_between_paid_refreshes {
+            if seconds == 0 || seconds > MAX_SECONDS_BETWEEN_REFRESHES {
+                return Err(ArisingError::InvalidRefreshInterval.into());
+            }
+
+            config.seconds_between_paid_refreshes = seconds;
+        }
+
+        if let Some(max) = max_characters {
+            if max < config.characters {
+                return Err(ArisingError::InvalidMaxCharacters.into());
+            }
+
+            config.max_characters = max;
+        }
+
+        emit!(ConfigUpdated {
+            seconds_between_refreshes: config.seconds_between_refreshes,
+            seconds_between_paid_refreshes: config.seconds_between_paid_refreshes,
+            max_characters: config.max_characters,
+        });
+
+        Ok(())
+    }
+
+    /// Proposes a new authority, it can be a wallet or a PDA of a multisig or governance
+    /// program. The default pubkey cancels a pending proposal.
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.pending_authority = new_authority;
+        msg!("Proposing {} as the new authority", new_authority);
+
+        Ok(())
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let previous_authority = config.authority;
+
+        config.authority = config.pending_authority;
+        config.pending_authority = Pubkey::default();
+
+        emit!(AuthorityTransferred {
+            previous_authority,
+            authority: config.authority,
+        });
+
+        Ok(())
+    }
+
+    pub fn add_operator(ctx: Context<AddOperator>, operator_key: Pubkey, roles: u16) -> Result<()> {
+        if (roles & !ALL_ROLES) != 0 {
+            return Err(ArisingError::InvalidRoles.into());
+        }
+
+        let operator = &mut ctx.accounts.operator;
+        operator.operator = operator_key;
+        operator.roles = roles;
+
+        Ok(())
+    }
+
+    pub fn update_operator(ctx: Context<UpdateOperator>, roles: u16) -> Result<()> {
+        if (roles & !ALL_ROLES) != 0 {
+            return Err(ArisingError::InvalidRoles.into());
+        }
+
+        let operator = &mut ctx.accounts.operator;
+        operator.roles = roles;
+        msg!("Changing operator {} roles to {}", operator.operator, roles);
+
+        Ok(())
+    }
+
+    pub fn set_refresher_mint(ctx: Context<SetRefresherMint>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.refresher_mint = ctx.accounts.refresher_mint.key();
+        msg!("Changing refresher mint to {}", config.refresher_mint);
+
+        Ok(())
+    }
+
+    /// Sets the collection and the creator every character NFT must be verified by.
+    pub fn set_character_collection(ctx: Context<SetCharacterCollection>, creator: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.character_collection = ctx.accounts.collection_mint.key();
+        config.character_creator = creator;
+        msg!("Changing character collection to {}", config.character_collection);
+
+        Ok(())
+    }
+
+    pub fn set_respec(
+        ctx: Context<SetRespec>,
+        respec_cost: u64,
+        seconds_between_respecs: u64
+    ) -> Result<()> {
+        if respec_cost > MAX_RESPEC_COST {
+            return Err(ArisingError::InvalidRespecCost.into());
+        }
+
+        if seconds_between_respecs > MAX_SECONDS_BETWEEN_RESPECS {
+            return Err(ArisingError::InvalidRespecInterval.into());
+        }
+
+        let config = &mut ctx.accounts.config;
+        config.respec_mint = ctx.accounts.respec_mint.key();
+        config.respec_cost = respec_cost;
+        config.seconds_between_respecs = seconds_between_respecs;
+        msg!(
+            "Changing respec to {} of mint {} every {} seconds",
+            respec_cost,
+            config.respec_mint,
+            seconds_between_respecs
+        );
+
+        Ok(())
+    }
+
+    pub fn set_experience_multiplier(
+        ctx: Context<SetExperienceMultiplier>,
+        multiplier: u64
+    ) -> Result<()> {
+        if multiplier == 0 {
+            return Err(ArisingError::InvalidExperienceMultiplier.into());
+        }
+
+        let config = &mut ctx.accounts.config;
+        config.experience_multiplier = multiplier;
+        msg!("Changing experience multiplier to {}", multiplier);
+
+        Ok(())
+    }
+
+    pub fn set_experience_event(
+        ctx: Context<SetExperienceMultiplier>,
+        multiplier: u64,
+        start: u64,
+        end: u64
+    ) -> Result<()> {
+        if multiplier == 0 {
+            return Err(ArisingError::InvalidExperienceMultiplier.into());
+        }
+
+        if start >= end {
+            return Err(ArisingError::InvalidExperienceEvent.into());
+        }
+
+        let config = &mut ctx.accounts.config;
+        config.experience_event_multiplier = multiplier;
+        config.experience_event_start = start;
+        config.experience_event_end = end;
+        msg!("Changing experience multiplier to {} from {} to {}", multiplier, start, end);
+
+        Ok(())
+    }
+
     pub fn add_character(ctx: Context<AddCharacter>, mint: Pubkey, _bump: u8) -> Result<()> {
         let character = &mut ctx.accounts.character;
         character.mint = mint;
 
+        let config = &mut ctx.accounts.config;
+        config.characters = checked_add(config.characters, 1)?;
+
         Ok(())
     }
 
     pub fn assign_stats_character(ctx: Context<CharacterAccess>, points: BaseStats) -> Result<()> {
         let character = &ctx.accounts.character;
 
-        if !can_assign_points(character, &points) {
+        if !can_assign_points(character, &points)? {
             return Err(CharacterError::NotEnoughAssignablePoints.into());
         }
 
         let mut_character = &mut ctx.accounts.character;
 
-        mut_character.base_stats.might += points.might;
-        mut_character.base_stats.speed += points.speed;
-        mut_character.base_stats.intellect += points.intellect;
-        mut_character.pool_stats.might += points.might;
-        mut_character.pool_stats.speed += points.speed;
-        mut_character.pool_stats.intellect += points.intellect;
+        let base_stats = &mut mut_character.base_stats;
+        base_stats.might = checked_add(base_stats.might, points.might)?;
+        base_stats.speed = checked_add(base_stats.speed, points.speed)?;
+        base_stats.intellect = checked_add(base_stats.intellect, points.intellect)?;
+
+        let pool_stats = &mut mut_character.pool_stats;
+        pool_stats.might = checked_add(pool_stats.might, points.might)?;
+        pool_stats.speed = checked_add(pool_stats.speed, points.speed)?;
+        pool_stats.intellect = checked_add(pool_stats.intellect, points.intellect)?;
 
         Ok(())
     }
@@ -78,6 +304,10 @@ pub mod arising {
         id: u32,
         data: Recipe
     ) -> Result<()> {
+        if !is_valid_forge_recipe(&data) {
+            return Err(ForgeError::InvalidMaterial.into());
+        }
+
         let recipe = &mut ctx.accounts.forge_recipe;
 
         let config = &mut ctx.accounts.config;
@@ -98,7 +328,7 @@ pub mod arising {
         recipe.recipe.item_rewarded_amount = data.item_rewarded_amount;
         recipe.recipe.available = false;
 
-        config.forge_recipes += 1;
+        config.forge_recipes = checked_add(config.forge_recipes, 1)?;
 
         Ok(())
     }
@@ -117,6 +347,10 @@ pub mod arising {
     }
 
     pub fn update_forge_recipe(ctx: Context<UpdateForgeRecipe>, data: Recipe) -> Result<()> {
+        if !is_valid_forge_recipe(&data) {
+            return Err(ForgeError::InvalidMaterial.into());
+        }
+
         let recipe = &mut ctx.accounts.forge_recipe;
 
         msg!("Updating forge recipe id {}", recipe.recipe.id);
@@ -136,7 +370,264 @@ pub mod arising {
         Ok(())
     }
 
+    pub fn add_craft_recipe(
+        ctx: Context<AddCraftRecipe>,
+        _bump: u8,
+        id: u32,
+        data: Recipe
+    ) -> Result<()> {
+        if !is_valid_craft_reward(&data) {
+            return Err(CraftError::InvalidReward.into());
+        }
+
+        if !is_defined_craft_reward(&data, &ctx.accounts.config) {
+            return Err(ItemError::InvalidID.into());
+        }
+
+        if !is_valid_craft_materials(&data) {
+            return Err(CraftError::InvalidMaterial.into());
+        }
+
+        let recipe = &mut ctx.accounts.craft_recipe;
+
+        let config = &mut ctx.accounts.config;
+
+        msg!("Adding {} with id {}", data.name, id);
+
+        recipe.recipe.id = id;
+        recipe.recipe.name = data.name;
+        recipe.recipe.materials = data.materials;
+        recipe.recipe.materials_amounts = data.materials_amounts;
+        recipe.recipe.materials_types = data.materials_types;
+        recipe.recipe.stats_required = data.stats_required;
+        recipe.recipe.stats_sacrificed = data.stats_sacrificed;
+        recipe.recipe.cooldown = data.cooldown;
+        recipe.recipe.level_required = data.level_required;
+        recipe.recipe.item_rewarded = data.item_rewarded;
+        recipe.recipe.item_rewarded_type = data.item_rewarded_type;
+        recipe.recipe.item_rewarded_amount = data.item_rewarded_amount;
+        recipe.recipe.available = false;
+
+        config.craft_recipes = checked_add(config.craft_recipes, 1)?;
+
+        Ok(())
+    }
+
+    pub fn update_craft_recipe_availability(
+        ctx: Context<UpdateCraftRecipe>,
+        available: bool
+    ) -> Result<()> {
+        let recipe = &mut ctx.accounts.craft_recipe;
+
+        msg!("Updating craft recipe id {} availability to {}", recipe.recipe.id, available);
+
+        recipe.recipe.available = available;
+
+        Ok(())
+    }
+
+    pub fn update_craft_recipe(ctx: Context<UpdateCraftRecipe>, data: Recipe) -> Result<()> {
+        if !is_valid_craft_reward(&data) {
+            return Err(CraftError::InvalidReward.into());
+        }
+
+        if !is_defined_craft_reward(&data, &ctx.accounts.config) {
+            return Err(ItemError::InvalidID.into());
+        }
+
+        if !is_valid_craft_materials(&data) {
+            return Err(CraftError::InvalidMaterial.into());
+        }
+
+        let recipe = &mut ctx.accounts.craft_recipe;
+
+        msg!("Updating craft recipe id {}", recipe.recipe.id);
+
+        recipe.recipe.name = data.name;
+        recipe.recipe.materials = data.materials;
+        recipe.recipe.materials_amounts = data.materials_amounts;
+        recipe.recipe.materials_types = data.materials_types;
+        recipe.recipe.stats_required = data.stats_required;
+        recipe.recipe.stats_sacrificed = data.stats_sacrificed;
+        recipe.recipe.cooldown = data.cooldown;
+        recipe.recipe.level_required = data.level_required;
+        recipe.recipe.item_rewarded = data.item_rewarded;
+        recipe.recipe.item_rewarded_type = data.item_rewarded_type;
+        recipe.recipe.item_rewarded_amount = data.item_rewarded_amount;
+
+        Ok(())
+    }
+
+    pub fn add_upgrade_recipe(
+        ctx: Context<AddUpgradeRecipe>,
+        _bump: u8,
+        id: u32,
+        data: UpgradeRecipe
+    ) -> Result<()> {
+        if data.success_rate > UPGRADE_RATE_PRECISION {
+            return Err(UpgradeError::InvalidSuccessRate.into());
+        }
+
+        if !are_valid_materials(&data.materials, &data.materials_types) {
+            return Err(UpgradeError::InvalidMaterial.into());
+        }
+
+        let recipe = &mut ctx.accounts.upgrade_recipe;
+
+        let config = &mut ctx.accounts.config;
+
+        msg!("Adding {} with id {}", data.name, id);
+
+        recipe.id = id;
+        recipe.name = data.name;
+        recipe.item_id = data.item_id;
+        recipe.tier_required = data.tier_required;
+        recipe.materials = data.materials;
+        recipe.materials_amounts = data.materials_amounts;
+        recipe.materials_types = data.materials_types;
+        recipe.stats_required = data.stats_required;
+        recipe.cooldown = data.cooldown;
+        recipe.level_required = data.level_required;
+        recipe.success_rate = data.success_rate;
+        recipe.stats_bonus = data.stats_bonus;
+        recipe.attributes_bonus = data.attributes_bonus;
+        recipe.available = false;
+
+        config.upgrade_recipes = checked_add(config.upgrade_recipes, 1)?;
+
+        Ok(())
+    }
+
+    pub fn update_upgrade_recipe_availability(
+        ctx: Context<UpdateUpgradeRecipe>,
+        available: bool
+    ) -> Result<()> {
+        let recipe = &mut ctx.accounts.upgrade_recipe;
+
+        msg!("Updating upgrade recipe id {} availability to {}", recipe.id, available);
+
+        recipe.available = available;
+
+        Ok(())
+    }
+
+    pub fn update_upgrade_recipe(
+        ctx: Context<UpdateUpgradeRecipe>,
+        data: UpgradeRecipe
+    ) -> Result<()> {
+        if data.success_rate > UPGRADE_RATE_PRECISION {
+            return Err(UpgradeError::InvalidSuccessRate.into());
+        }
+
+        if !are_valid_materials(&data.materials, &data.materials_types) {
+            return Err(UpgradeError::InvalidMaterial.into());
+        }
+
+        let recipe = &mut ctx.accounts.upgrade_recipe;
+
+        msg!("Updating upgrade recipe id {}", recipe.id);
+
+        recipe.name = data.name;
+        recipe.item_id = data.item_id;
+        recipe.tier_required = data.tier_required;
+        recipe.materials = data.materials;
+        recipe.materials_amounts = data.materials_amounts;
+        recipe.materials_types = data.materials_types;
+        recipe.stats_required = data.stats_required;
+        recipe.cooldown = data.cooldown;
+        recipe.level_required = data.level_required;
+        recipe.success_rate = data.success_rate;
+        recipe.stats_bonus = data.stats_bonus;
+        recipe.attributes_bonus = data.attributes_bonus;
+
+        Ok(())
+    }
+
+    pub fn add_item_definition(
+        ctx: Context<AddItemDefinition>,
+        _bump: u8,
+        id: u32,
+        data: ItemDefinition
+    ) -> Result<()> {
+        if !is_valid_item_type(data.item_type) {
+            return Err(ItemError::InvalidType.into());
+        }
+
+        let item_definition = &mut ctx.accounts.item_definition;
+
+        let config = &mut ctx.accounts.config;
+
+        msg!("Adding {} with id {}", data.name, id);
+
+        item_definition.id = id;
+        item_definition.name = data.name;
+        item_definition.uri = data.uri;
+        item_definition.item_type = data.item_type;
+        item_definition.stats_additions = data.stats_additions;
+        item_definition.attributes_additions = data.attributes_additions;
+        item_definition.stats_reductions = data.stats_reductions;
+        item_definition.attributes_reductions = data.attributes_reductions;
+        item_definition.level_required = data.level_required;
+
+        config.item_definitions = checked_add(config.item_definitions, 1)?;
+
+        Ok(())
+    }
+
+    pub fn update_item_definition(
+        ctx: Context<UpdateItemDefinition>,
+        data: ItemDefinition
+    ) -> Result<()> {
+        if !is_valid_item_type(data.item_type) {
+            return Err(ItemError::InvalidType.into());
+        }
+
+        let item_definition = &mut ctx.accounts.item_definition;
+
+        msg!("Updating item definition id {}", item_definition.id);
+
+        item_definition.name = data.name;
+        item_definition.uri = data.uri;
+        item_definition.item_type = data.item_type;
+        item_definition.stats_additions = data.stats_additions;
+        item_definition.attributes_additions = data.attributes_additions;
+        item_definition.stats_reductions = data.stats_reductions;
+        item_definition.attributes_reductions = data.attributes_reductions;
+        item_definition.level_required = data.level_required;
+
+        Ok(())
+    }
+
+    pub fn add_material_mint(
+        ctx: Context<AddMaterialMint>,
+        material_type: u16,
+        material: u32
+    ) -> Result<()> {
+        if !is_valid_material(material_type, material) {
+            return Err(MaterialError::InvalidMaterial.into());
+        }
+
+        let material_mint_registry = &mut ctx.accounts.material_mint_registry;
+
+        material_mint_registry.material_type = material_type;
+        material_mint_registry.material = material;
+        material_mint_registry.mint = ctx.accounts.material_mint.key();
+
+        msg!(
+            "Adding mint {} for material {} of type {}",
+            material_mint_registry.mint,
+            material,
+            material_type
+        );
+
+        Ok(())
+    }
+
     pub fn add_quest(ctx: Context<AddQuest>, _bump: u8, id: u32, data: Quest) -> Result<()> {
+        if !is_valid_quest_reward(&data) {
+            return Err(QuestError::InvalidMaterial.into());
+        }
+
         let quest = &mut ctx.accounts.quest;
 
         let config = &mut ctx.accounts.config;
@@ -158,7 +649,7 @@ pub mod arising {
         quest.mob_base_attributes = data.mob_base_attributes;
         quest.available = false;
 
-        config.quests += 1;
+        config.quests = checked_add(config.quests, 1)?;
 
         Ok(())
     }
@@ -174,6 +665,10 @@ pub mod arising {
     }
 
     pub fn update_quest(ctx: Context<UpdateQuest>, data: Quest) -> Result<()> {
+        if !is_valid_quest_reward(&data) {
+            return Err(QuestError::InvalidMaterial.into());
+        }
+
         let quest = &mut ctx.accounts.quest;
 
         msg!("Updating quest id {}", quest.id);
@@ -194,11 +689,11 @@ pub mod arising {
         Ok(())
     }
 
-    pub fn perform_refresh(ctx: Context<CharacterAccess>) -> Result<()> {
+    pub fn perform_refresh(ctx: Context<CharacterRefresh>) -> Result<()> {
         let character = &ctx.accounts.character;
         let config = &ctx.accounts.config;
 
-        if !can_refresh(config, character) {
+        if !can_refresh(config, character)? {
             return Err(CharacterError::RefreshNotAvailable.into());
         }
 
@@ -209,14 +704,17 @@ pub mod arising {
         return Ok(());
     }
 
-    pub fn perform_refresh_with_token(ctx: Context<CharacterAccess>) -> Result<()> {
+    pub fn perform_refresh_with_token(ctx: Context<CharacterRefreshWithToken>) -> Result<()> {
         let character = &ctx.accounts.character;
         let config = &ctx.accounts.config;
 
-        if !can_refresh_with_token(config, character) {
+        if !can_refresh_with_token(config, character)? {
             return Err(CharacterError::RefreshNotAvailable.into());
         }
 
+        // Pay the refresh with a refresher token
+        burn_refresher(ctx.accounts)?;
+
         let mut_character = &mut ctx.accounts.character;
 
         refresh_with_token(mut_character);
@@ -224,9 +722,33 @@ pub mod arising {
         return Ok(());
     }
 
+    pub fn respec_character(ctx: Context<CharacterRespec>) -> Result<()> {
+        let character = &ctx.accounts.character;
+        let config = &ctx.accounts.config;
+
+        if !can_respec(config, character)? {
+            return Err(CharacterError::RespecNotAvailable.into());
+        }
+
+        // Pay the respec with the configured tokens
+        if config.respec_cost > 0 {
+            burn_respec_cost(ctx.accounts, config.respec_cost)?;
+        }
+
+        let mut_character = &mut ctx.accounts.character;
+
+        respec(mut_character);
+
+        msg!("Respec of character {}", mut_character.mint);
+
+        return Ok(());
+    }
+
     pub fn start_forge(ctx: Context<ForgeAccess>) -> Result<()> {
         let recipe = &ctx.accounts.forge_recipe;
         let character = &ctx.accounts.character;
+        let character_materials = &ctx.accounts.character_materials;
+        let character_slots = &ctx.accounts.character_slots;
 
         // Check if the forge recipe is available globally.
         if !recipe.recipe.available {
@@ -234,7 +756,7 @@ pub mod arising {
         }
 
         // Check if the character is able to forge
-        if !is_slot_available(&character.craft) {
+        if !is_slot_available(&character_slots.forge) {
             return Err(CharacterError::NotAbleToForgeRecipe.into());
         }
 
@@ -248,67 +770,572 @@ pub mod arising {
             return Err(CharacterError::NotEnoughPoolPointsToConsume.into());
         }
 
+        // Check if the character has the base points the recipe sacrifices
+        if !can_sacrifice(character, &recipe.recipe.stats_sacrificed) {
+            return Err(CharacterError::NotEnoughBasePointsToSacrifice.into());
+        }
+
         let materials = &recipe.recipe.materials;
         let amounts = &recipe.recipe.materials_amounts;
         let types = &recipe.recipe.materials_types;
 
         // Check if the character can consume the materials for the recipe
-        if !has_enough_materials(character, materials, amounts, types) {
+        if !has_enough_materials(character_materials, materials, amounts, types) {
             return Err(CharacterError::NotEnoughResources.into());
         }
 
-        let mut_character = &mut ctx.accounts.character;
+        let mut_character_materials = &mut ctx.accounts.character_materials;
 
         // Consume the recipe material
-        consume_materials(mut_character, materials, amounts, types);
+        consume_materials(mut_character_materials, materials, amounts, types)?;
 
         let stats = &recipe.recipe.stats_required;
 
+        let mut_character = &mut ctx.accounts.character;
+
         // Consume the pool points
-        consume_points(mut_character, stats);
+        consume_points(mut_character, stats)?;
+
+        // Sacrifice the base points for good
+        sacrifice_points(mut_character, &recipe.recipe.stats_sacrificed)?;
+
+        let mut_character_slots = &mut ctx.accounts.character_slots;
 
         // Store the recipe information for claim later
-        mut_character.forge.cooldown = now() + (recipe.recipe.cooldown as u64);
-        mut_character.forge.last_task_id = recipe.recipe.id;
-        mut_character.forge.last_task_claimed = false;
+        mut_character_slots.forge.cooldown = now() + (recipe.recipe.cooldown as u64);
+        mut_character_slots.forge.last_task_id = recipe.recipe.id;
+        mut_character_slots.forge.last_task_claimed = false;
 
         Ok(())
     }
 
     pub fn claim_forge(ctx: Context<ForgeAccess>) -> Result<()> {
         let recipe = &ctx.accounts.forge_recipe;
-        let character = &ctx.accounts.character;
+        let character_slots = &ctx.accounts.character_slots;
 
         // Check if the character is able to claim the forge recipe
-        if !is_slot_claimable(&character.forge) {
+        if !is_slot_claimable(&character_slots.forge) {
             return Err(CharacterError::NotAbleToClaimForgeRecipe.into());
         }
 
+        // Check the recipe is the one that was started
+        if recipe.recipe.id != character_slots.forge.last_task_id {
+            return Err(ForgeError::InvalidID.into());
+        }
+
         let material = recipe.recipe.item_rewarded;
         let amount = recipe.recipe.item_rewarded_amount;
         let material_type = recipe.recipe.item_rewarded_type;
 
-        let mut_character = &mut ctx.accounts.character;
+        let mut_character_materials = &mut ctx.accounts.character_materials;
 
         // Reward the character
-        forge_reward(mut_character, material, amount, material_type);
+        forge_reward(mut_character_materials, material, amount, material_type)?;
+
+        let mut_character_slots = &mut ctx.accounts.character_slots;
 
         // Modify the character forge slot to be able to create another recipe
-        mut_character.forge.last_task_claimed = true;
+        mut_character_slots.forge.last_task_claimed = true;
+
+        Ok(())
+    }
+
+    pub fn start_craft(ctx: Context<CraftAccess>) -> Result<()> {
+        let recipe = &ctx.accounts.craft_recipe;
+        let character = &ctx.accounts.character;
+        let character_materials = &ctx.accounts.character_materials;
+        let character_slots = &ctx.accounts.character_slots;
+
+        // Check if the craft recipe is available globally.
+        if !recipe.recipe.available {
+            return Err(CraftError::NotAvailable.into());
+        }
+
+        // Check if the character is able to craft
+        if !is_slot_available(&character_slots.craft) {
+            return Err(CharacterError::NotAbleToCraftRecipe.into());
+        }
+
+        // Check if the character has enough level for the recipe
+        if recipe.recipe.level_required > character.level {
+            return Err(CharacterError::NotEnoughLevel.into());
+        }
+
+        // Check if the character can consume points of the pool
+        if !can_consume(character, &recipe.recipe.stats_required) {
+            return Err(CharacterError::NotEnoughPoolPointsToConsume.into());
+        }
+
+        // Check if the character has the base points the recipe sacrifices
+        if !can_sacrifice(character, &recipe.recipe.stats_sacrificed) {
+            return Err(CharacterError::NotEnoughBasePointsToSacrifice.into());
+        }
+
+        let materials = &recipe.recipe.materials;
+        let amounts = &recipe.recipe.materials_amounts;
+        let types = &recipe.recipe.materials_types;
+
+        // Check if the character can consume the materials for the recipe
+        if !has_enough_materials(character_materials, materials, amounts, types) {
+            return Err(CharacterError::NotEnoughResources.into());
+        }
+
+        let mut_character_materials = &mut ctx.accounts.character_materials;
+
+        // Consume the recipe material
+        consume_materials(mut_character_materials, materials, amounts, types)?;
+
+        let mut_character = &mut ctx.accounts.character;
+
+        // Consume the pool points
+        consume_points(mut_character, &recipe.recipe.stats_required)?;
+
+        // Sacrifice the base points for good
+        sacrifice_points(mut_character, &recipe.recipe.stats_sacrificed)?;
+
+        let mut_character_slots = &mut ctx.accounts.character_slots;
+
+        // Store the recipe information for claim later
+        mut_character_slots.craft.cooldown = now() + (recipe.recipe.cooldown as u64);
+        mut_character_slots.craft.last_task_id = recipe.recipe.id;
+        mut_character_slots.craft.last_task_claimed = false;
+
+        Ok(())
+    }
+
+    pub fn claim_craft(ctx: Context<ClaimCraft>) -> Result<()> {
+        let recipe = &ctx.accounts.craft_recipe;
+        let character_slots = &ctx.accounts.character_slots;
+
+        // Check if the character is able to claim the craft recipe
+        if !is_slot_claimable(&character_slots.craft) {
+            return Err(CharacterError::NotAbleToClaimCraftRecipe.into());
+        }
+
+        // Check the recipe is the one that was started
+        if recipe.recipe.id != character_slots.craft.last_task_id {
+            return Err(CraftError::InvalidID.into());
+        }
+
+        // Reward the payer with the crafted item token
+        mint_item(ctx.accounts, *ctx.bumps.get("item_authority").unwrap())?;
+
+        let item_id = recipe.recipe.item_rewarded;
+        let item_mint = ctx.accounts.item_mint.key();
+
+        let item = &mut ctx.accounts.item;
+
+        item.id = item_id;
+        item.mint = item_mint;
+        item.tier = 0;
+        item.durability = MAX_DURABILITY;
+        item.stats_bonus = BaseStats::default();
+        item.attributes_bonus = BaseAttributes::default();
+        item.upgrading = false;
+        item.equipped = false;
+
+        msg!("Crafted item id {} with mint {}", item.id, item.mint);
+
+        let mut_character_slots = &mut ctx.accounts.character_slots;
+
+        // Modify the character craft slot to be able to create another recipe
+        mut_character_slots.craft.last_task_claimed = true;
+
+        Ok(())
+    }
+
+    pub fn start_upgrade(ctx: Context<UpgradeAccess>, commitment: [u8; 32]) -> Result<()> {
+        let recipe = &ctx.accounts.upgrade_recipe;
+        let character = &ctx.accounts.character;
+        let character_materials = &ctx.accounts.character_materials;
+        let character_slots = &ctx.accounts.character_slots;
+        let item = &ctx.accounts.item;
+
+        // Check if the upgrade recipe is available globally.
+        if !recipe.available {
+            return Err(UpgradeError::NotAvailable.into());
+        }
+
+        // Check if the character is able to upgrade
+        if !is_slot_available(&character_slots.upgrade) {
+            return Err(CharacterError::NotAbleToUpgradeItem.into());
+        }
+
+        // Check if the recipe applies to the item and its current tier
+        if !can_upgrade_item(item, recipe) {
+            return Err(UpgradeError::InvalidItem.into());
+        }
+
+        // Equipped items or items already being upgraded can't be upgraded
+        if item.upgrading || item.equipped {
+            return Err(ItemError::ItemLocked.into());
+        }
+
+        // Check if the character has enough level for the recipe
+        if recipe.level_required > character.level {
+            return Err(CharacterError::NotEnoughLevel.into());
+        }
+
+        // Check if the character can consume points of the pool
+        if !can_consume(character, &recipe.stats_required) {
+            return Err(CharacterError::NotEnoughPoolPointsToConsume.into());
+        }
+
+        let materials = &recipe.materials;
+        let amounts = &recipe.materials_amounts;
+        let types = &recipe.materials_types;
+
+        // Check if the character can consume the materials for the recipe
+        if !has_enough_materials(character_materials, materials, amounts, types) {
+            return Err(CharacterError::NotEnoughResources.into());
+        }
+
+        let mut_character_materials = &mut ctx.accounts.character_materials;
+
+        // Consume the recipe material
+        consume_materials(mut_character_materials, materials, amounts, types)?;
+
+        let mut_character = &mut ctx.accounts.character;
+
+        // Consume the pool points
+        consume_points(mut_character, &recipe.stats_required)?;
+
+        // Lock the item until the upgrade is claimed
+        freeze_item(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.item_token_account.to_account_info(),
+            ctx.accounts.item_mint.to_account_info(),
+            ctx.accounts.item_authority.to_account_info(),
+            *ctx.bumps.get("item_authority").unwrap()
+        )?;
+
+        let mut_item = &mut ctx.accounts.item;
+        mut_item.upgrading = true;
+
+        let item_mint = mut_item.mint;
+
+        let mut_character_slots = &mut ctx.accounts.character_slots;
+
+        // Store the commitment to be revealed against a later slot hash on claim
+        mut_character_slots.upgrade.commitment = commitment;
+        mut_character_slots.upgrade.commitment_slot = current_slot();
+        mut_character_slots.upgrade.slot_hash = [0; 32];
+        mut_character_slots.upgrade.slot_hash_stored = false;
+        mut_character_slots.upgrade.item = item_mint;
+        mut_character_slots.upgrade.cooldown = now() + (recipe.cooldown as u64);
+        mut_character_slots.upgrade.last_task_id = recipe.id;
+        mut_character_slots.upgrade.last_task_claimed = false;
+
+        Ok(())
+    }
+
+    pub fn claim_upgrade(ctx: Context<UpgradeAccess>, reveal: [u8; 32]) -> Result<()> {
+        let recipe = &ctx.accounts.upgrade_recipe;
+        let character_slots = &ctx.accounts.character_slots;
+        let item = &ctx.accounts.item;
+
+        // Check if the character is able to claim the upgrade
+        if !is_slot_claimable(&character_slots.upgrade) {
+            return Err(CharacterError::NotAbleToClaimUpgrade.into());
+        }
+
+        // Check the recipe is the one that was started
+        if recipe.id != character_slots.upgrade.last_task_id {
+            return Err(UpgradeError::InvalidID.into());
+        }
+
+        // Check the item is the one that was locked on start
+        if item.mint != character_slots.upgrade.item {
+            return Err(UpgradeError::InvalidLockedItem.into());
+        }
+
+        if !item.upgrading {
+            return Err(UpgradeError::ItemNotUpgrading.into());
+        }
+
+        // Check the recipe still applies to the item and its current tier
+        if !can_upgrade_item(item, recipe) {
+            return Err(UpgradeError::InvalidItem.into());
+        }
+
+        if !is_valid_reveal(&character_slots.upgrade.commitment, &reveal) {
+            return Err(UpgradeError::InvalidReveal.into());
+        }
+
+        let slot_hashes = ctx.accounts.slot_hashes.try_borrow_data()?;
+
+        // The hash of the slot following the commitment, unknown when it was stored
+        let slot_hash = match task_slot_hash(&character_slots.upgrade, &slot_hashes) {
+            SlotHashLookup::Found(hash) => Some(hash),
+            SlotHashLookup::Pending => {
+                return Err(UpgradeError::EntropyNotAvailable.into());
+            }
+            SlotHashLookup::Expired => None,
+        };
+
+        thaw_item(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.item_token_account.to_account_info(),
+            ctx.accounts.item_mint.to_account_info(),
+            ctx.accounts.item_authority.to_account_info(),
+            *ctx.bumps.get("item_authority").unwrap()
+        )?;
+
+        let mut_item = &mut ctx.accounts.item;
+
+        // A failed upgrade keeps the item as it was, only the materials are lost. An upgrade
+        // whose slot hash aged out before being stored fails as well
+        if let Some(slot_hash) = slot_hash {
+            let mut rng = Rng::new(&mix_entropy(&reveal, &slot_hash));
+
+            if is_upgrade_successful(recipe.success_rate, &mut rng) {
+                upgrade_item(mut_item, recipe)?;
+                msg!("Upgraded item id {} to tier {}", mut_item.id, mut_item.tier);
+            } else {
+                msg!("Upgrade of item id {} failed", mut_item.id);
+            }
+        } else {
+            msg!("Upgrade of item id {} failed, its slot hash aged out", mut_item.id);
+        }
+
+        mut_item.upgrading = false;
+
+        // Modify the character upgrade slot to be able to start another upgrade
+        let mut_character_slots = &mut ctx.accounts.character_slots;
+        mut_character_slots.upgrade.item = Pubkey::default();
+        mut_character_slots.upgrade.last_task_claimed = true;
+
+        Ok(())
+    }
+
+    pub fn equip_item(ctx: Context<EquipmentAccess>, slot: u16) -> Result<()> {
+        let character = &ctx.accounts.character;
+        let item = &ctx.accounts.item;
+        let item_definition = &ctx.accounts.item_definition;
+
+        let slot = match equipment_slot(slot) {
+            Some(slot) => slot,
+            None => {
+                return Err(ItemError::InvalidSlot.into());
+            }
+        };
+
+        // Check the item is not being upgraded or already equipped
+        if item.upgrading || item.equipped {
+            return Err(ItemError::ItemLocked.into());
+        }
+
+        // Check the item type fits the slot
+        if !fits_slot(item_definition.item_type, slot) {
+            return Err(ItemError::InvalidSlot.into());
+        }
+
+        // Check if the character has enough level for the item
+        if item_definition.level_required > character.level {
+            return Err(CharacterError::NotEnoughLevel.into());
+        }
+
+        let item_key = item.key();
+        let two_handed = item_definition.item_type == (ItemType::TwoHanded as u16);
+
+        let equipment = &mut ctx.accounts.character_equipment;
+
+        if *equipped_item(equipment, slot) != Pubkey::default() {
+            return Err(ItemError::SlotOccupied.into());
+        }
+
+        // Two handed items take both hands
+        if two_handed {
+            let other = equipped_item(equipment, other_hand(slot));
+
+            if *other != Pubkey::default() {
+                return Err(ItemError::HandsOccupied.into());
+            }
+
+            *other = item_key;
+        }
+
+        *equipped_item(equipment, slot) = item_key;
+
+        // Lock the item while it is equipped
+        freeze_item(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.item_token_account.to_account_info(),
+            ctx.accounts.item_mint.to_account_info(),
+            ctx.accounts.item_authority.to_account_info(),
+            *ctx.bumps.get("item_authority").unwrap()
+        )?;
+
+        let mut_item = &mut ctx.accounts.item;
+        mut_item.equipped = true;
+
+        msg!("Equipped item id {} with mint {}", mut_item.id, mut_item.mint);
+
+        Ok(())
+    }
+
+    pub fn unequip_item(ctx: Context<UnequipAccess>, slot: u16) -> Result<()> {
+        let item = &ctx.accounts.item;
+
+        let slot = match equipment_slot(slot) {
+            Some(slot) => slot,
+            None => {
+                return Err(ItemError::InvalidSlot.into());
+            }
+        };
+
+        let item_key = item.key();
+
+        let equipment = &mut ctx.accounts.character_equipment;
+
+        if *equipped_item(equipment, slot) != item_key {
+            return Err(ItemError::ItemNotEquipped.into());
+        }
+
+        // Two handed items free both hands, whatever the item type is now
+        clear_equipped(equipment, item_key);
+
+        thaw_item(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.item_token_account.to_account_info(),
+            ctx.accounts.item_mint.to_account_info(),
+            ctx.accounts.item_authority.to_account_info(),
+            *ctx.bumps.get("item_authority").unwrap()
+        )?;
+
+        let mut_item = &mut ctx.accounts.item;
+        mut_item.equipped = false;
+
+        msg!("Unequipped item id {} with mint {}", mut_item.id, mut_item.mint);
+
+        Ok(())
+    }
+
+    /// Emits the character effective stats as return data.
+    /// The equipped items are given as `(item, item_definition)` pairs in the remaining accounts.
+    pub fn character_effective_stats(ctx: Context<CharacterStatsView>) -> Result<()> {
+        let character = &ctx.accounts.character;
+        let equipment = &ctx.accounts.character_equipment;
+
+        let items = load_equipped_items(equipment, ctx.remaining_accounts)?;
+        let effective = effective_stats(&character.base_stats, character.level, &items);
+
+        set_return_data(&effective.try_to_vec()?);
+
+        Ok(())
+    }
+
+    pub fn withdraw_materials(
+        ctx: Context<MaterialAccess>,
+        material_type: u16,
+        material: u32,
+        amount: u32
+    ) -> Result<()> {
+        if !is_valid_material(material_type, material) {
+            return Err(MaterialError::InvalidMaterial.into());
+        }
+
+        if amount == 0 {
+            return Err(MaterialError::InvalidAmount.into());
+        }
+
+        let character_materials = &mut ctx.accounts.character_materials;
+        let available = material_amount(character_materials, material_type, material);
+
+        // Check the character holds the materials to withdraw
+        if *available < amount {
+            return Err(CharacterError::NotEnoughResources.into());
+        }
+
+        *available = checked_sub(*available, amount)?;
+
+        mint_materials(
+            ctx.accounts,
+            amount as u64,
+            *ctx.bumps.get("material_authority").unwrap()
+        )?;
+
+        msg!("Withdrawn {} of material {} of type {}", amount, material, material_type);
 
         Ok(())
     }
 
-    pub fn start_quest(ctx: Context<QuestAccess>, seed: u64) -> Result<()> {
+    pub fn deposit_materials(
+        ctx: Context<MaterialAccess>,
+        material_type: u16,
+        material: u32,
+        amount: u32
+    ) -> Result<()> {
+        if !is_valid_material(material_type, material) {
+            return Err(MaterialError::InvalidMaterial.into());
+        }
+
+        if amount == 0 {
+            return Err(MaterialError::InvalidAmount.into());
+        }
+
+        if ctx.accounts.material_token_account.amount < (amount as u64) {
+            return Err(CharacterError::NotEnoughResources.into());
+        }
+
+        burn_materials(ctx.accounts, amount as u64)?;
+
+        let character_materials = &mut ctx.accounts.character_materials;
+        let available = material_amount(character_materials, material_type, material);
+
+        *available = checked_add(*available, amount)?;
+
+        msg!("Deposited {} of material {} of type {}", amount, material, material_type);
+
+        Ok(())
+    }
+
+    pub fn transfer_materials(
+        ctx: Context<TransferMaterials>,
+        material_type: u16,
+        material: u32,
+        amount: u32
+    ) -> Result<()> {
+        if !is_valid_material(material_type, material) {
+            return Err(MaterialError::InvalidMaterial.into());
+        }
+
+        if amount == 0 {
+            return Err(MaterialError::InvalidAmount.into());
+        }
+
+        let source = material_amount(&mut ctx.accounts.character_materials, material_type, material);
+        *source = source.checked_sub(amount).ok_or(CharacterError::NotEnoughResources)?;
+
+        let destination = material_amount(
+            &mut ctx.accounts.destination_character_materials,
+            material_type,
+            material
+        );
+        *destination = checked_add(*destination, amount)?;
+
+        emit!(MaterialsTransferred {
+            from: ctx.accounts.character.mint,
+            to: ctx.accounts.destination_character.mint,
+            material_type,
+            material,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    pub fn start_quest(ctx: Context<QuestAccess>, commitment: [u8; 32]) -> Result<()> {
         let quest = &ctx.accounts.quest;
         let character = &ctx.accounts.character;
+        let character_slots = &ctx.accounts.character_slots;
 
         if !quest.available {
             return Err(QuestError::NotAvailable.into());
         }
 
         // Check if the character is able to start the quest
-        if !is_slot_available(&character.quest) {
+        if !is_slot_available(&character_slots.quest) {
             return Err(CharacterError::NotAbleToQuest.into());
         }
 
@@ -323,29 +1350,57 @@ pub mod arising {
         }
 
         let mut_character = &mut ctx.accounts.character;
-        consume_points(mut_character, &quest.stats_required);
+        consume_points(mut_character, &quest.stats_required)?;
 
-        if quest.quest_type == (QuestType::Raid as u16) {
-            // TODO: add the seed for randomness.
-        }
+        let mut_character_slots = &mut ctx.accounts.character_slots;
+
+        // Store the commitment to be revealed against a later slot hash on a raid claim,
+        // overwriting the one of the previous quest
+        mut_character_slots.quest.commitment = commitment;
+        mut_character_slots.quest.commitment_slot = current_slot();
+        mut_character_slots.quest.slot_hash = [0; 32];
+        mut_character_slots.quest.slot_hash_stored = false;
 
-        mut_character.quest.cooldown = now() + (quest.cooldown as u64);
-        mut_character.quest.last_task_id = quest.id;
-        mut_character.quest.last_task_claimed = false;
+        mut_character_slots.quest.cooldown = now() + (quest.cooldown as u64);
+        mut_character_slots.quest.last_task_id = quest.id;
+        mut_character_slots.quest.last_task_claimed = false;
 
         Ok(())
     }
 
-    pub fn claim_quest(ctx: Context<QuestAccess>) -> Result<()> {
+    /// Stores the slot hashes the pending raid and upgrade settle with, so they can still be
+    /// claimed once the hashes aged out of the SlotHashes sysvar. Anyone can store them, the
+    /// hashes can't be chosen.
+    pub fn store_slot_hashes(ctx: Context<StoreSlotHashes>) -> Result<()> {
+        let slot_hashes = ctx.accounts.slot_hashes.try_borrow_data()?;
+        let character_slots = &mut ctx.accounts.character_slots;
+
+        let quest_stored = store_slot_hash(&mut character_slots.quest, &slot_hashes);
+        let upgrade_stored = store_slot_hash(&mut character_slots.upgrade, &slot_hashes);
+
+        if !quest_stored && !upgrade_stored {
+            return Err(ArisingError::SlotHashNotStored.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn claim_quest(ctx: Context<QuestAccess>, reveal: [u8; 32]) -> Result<()> {
         let quest = &ctx.accounts.quest;
         let character = &ctx.accounts.character;
+        let character_slots = &ctx.accounts.character_slots;
 
         // Check if the character is able to claim the craft recipe
-        if !is_slot_claimable(&character.quest) {
+        if !is_slot_claimable(&character_slots.quest) {
             return Err(CharacterError::NotAbleToClaimQuest.into());
         }
 
-        let mut_character = &mut ctx.accounts.character;
+        // Check the quest is the one that was started
+        if quest.id != character_slots.quest.last_task_id {
+            return Err(QuestError::InvalidID.into());
+        }
+
+        let mut_character_materials = &mut ctx.accounts.character_materials;
         let materials = &quest.materials_reward;
         let amounts = &quest.materials_amounts;
 
@@ -353,14 +1408,62 @@ pub mod arising {
             quest.quest_type == (QuestType::Farm as u16) ||
             quest.quest_type == (QuestType::Job as u16)
         {
-            quest_rewards(mut_character, materials, amounts);
+            quest_rewards(mut_character_materials, materials, amounts)?;
         } else {
-            // TODO: perform fight and experience reward.
+            if !is_valid_reveal(&character_slots.quest.commitment, &reveal) {
+                return Err(QuestError::InvalidReveal.into());
+            }
+
+            let slot_hashes = ctx.accounts.slot_hashes.try_borrow_data()?;
+
+            // The hash of the slot following the commitment, unknown when it was stored
+            let slot_hash = match task_slot_hash(&character_slots.quest, &slot_hashes) {
+                SlotHashLookup::Found(hash) => Some(hash),
+                SlotHashLookup::Pending => {
+                    return Err(QuestError::EntropyNotAvailable.into());
+                }
+                SlotHashLookup::Expired => None,
+            };
+
+            // A raid whose slot hash aged out before being stored is settled as lost
+            if let Some(slot_hash) = slot_hash {
+                let mut rng = Rng::new(&mix_entropy(&reveal, &slot_hash));
+
+                // The equipped items are given in the remaining accounts
+                let items = load_equipped_items(&ctx.accounts.character_equipment, ctx.remaining_accounts)?;
+                let effective = effective_stats(&character.base_stats, character.level, &items);
+
+                let fighter = Combatant {
+                    level: character.level,
+                    stats: effective.stats,
+                    attributes: effective.attributes,
+                };
+
+                let mob = Combatant {
+                    level: quest.mob_level,
+                    stats: quest.mob_base_stats.clone(),
+                    attributes: quest.mob_base_attributes.clone(),
+                };
+
+                let result = fight(&fighter, &mob, &mut rng);
+
+                msg!("Raid quest id {} resolved in {} rounds", quest.id, result.rounds);
+
+                // Only a won fight rewards the character
+                if result.outcome == FightOutcome::Win {
+                    raid_rewards(mut_character_materials, materials, amounts, &mut rng)?;
+
+                    let mut_character = &mut ctx.accounts.character;
+                    add_experience(mut_character, &ctx.accounts.config, quest.mob_experience as u64)?;
+                }
+            } else {
+                msg!("Raid quest id {} lost, its slot hash aged out", quest.id);
+            }
         }
 
         // Modify the character slot to be able to start another quest
-        let mut_character = &mut ctx.accounts.character;
-        mut_character.quest.last_task_claimed = true;
+        let mut_character_slots = &mut ctx.accounts.character_slots;
+        mut_character_slots.quest.last_task_claimed = true;
 
         Ok(())
     }
@@ -368,12 +1471,90 @@ pub mod arising {
 
 #[derive(Accounts)]
 pub struct SetPause<'info> {
+    #[account(
+        constraint = is_authorized(&config, authority.key(), &operator, Role::Pause) @ ArisingError::InvalidAuthority)]
+    authority: Signer<'info>,
+
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+
+    pub operator: Option<Account<'info, Operator>>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ArisingError::InvalidAuthority)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pending_authority: Signer<'info>,
+
     #[account(mut,
-        constraint = payer.key() == config.authority)]
-    payer: Signer<'info>,
+        constraint = config.pending_authority != Pubkey::default() @ ArisingError::InvalidPendingAuthority,
+        constraint = config.pending_authority == pending_authority.key() @ ArisingError::InvalidPendingAuthority)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ArisingError::InvalidAuthority)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct SetRefresherMint<'info> {
+    #[account(
+        constraint = is_authorized(&config, authority.key(), &operator, Role::Economy) @ ArisingError::InvalidAuthority)]
+    authority: Signer<'info>,
+
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+
+    pub operator: Option<Account<'info, Operator>>,
+
+    pub refresher_mint: Account<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct SetCharacterCollection<'info> {
+    authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ArisingError::InvalidAuthority)]
+    pub config: Account<'info, Config>,
+
+    pub collection_mint: Account<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct SetExperienceMultiplier<'info> {
+    #[account(
+        constraint = is_authorized(&config, authority.key(), &operator, Role::Economy) @ ArisingError::InvalidAuthority)]
+    authority: Signer<'info>,
+
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+
+    pub operator: Option<Account<'info, Operator>>,
+}
+
+#[derive(Accounts)]
+pub struct SetRespec<'info> {
+    #[account(
+        constraint = is_authorized(&config, authority.key(), &operator, Role::Economy) @ ArisingError::InvalidAuthority)]
+    authority: Signer<'info>,
 
     #[account(mut)]
     pub config: Account<'info, Config>,
+
+    pub operator: Option<Account<'info, Operator>>,
+
+    pub respec_mint: Account<'info, Mint>,
 }
 
 #[derive(Accounts)]
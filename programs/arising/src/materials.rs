@@ -0,0 +1,234 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{ self, Burn, Mint, MintTo, Token, TokenAccount };
+
+use crate::characters::*;
+use crate::codex::*;
+use crate::config::*;
+use crate::errors::*;
+use crate::operators::*;
+
+pub const MATERIAL_MINT_PREFIX: &str = "arising_material_mint";
+pub const MATERIAL_AUTHORITY_PREFIX: &str = "arising_material_authority";
+
+/// The amount of materials of each type a character can hold.
+pub const MATERIALS_PER_TYPE: u32 = 20;
+
+/// Checks the material is a known raw or basic material.
+#[inline(always)]
+pub fn is_valid_material(material_type: u16, material: u32) -> bool {
+    if
+        material_type != (ResourceType::Raw as u16) &&
+        material_type != (ResourceType::Basic as u16)
+    {
+        return false;
+    }
+
+    return (1..=MATERIALS_PER_TYPE).contains(&material);
+}
+
+/// Checks every material of a recipe is known, empty entries have a 0 material.
+pub fn are_valid_materials(materials: &[u32; 10], types: &[u16; 10]) -> bool {
+    let mut i: usize = 0;
+
+    loop {
+        if i >= 10 {
+            break;
+        }
+
+        if materials[i] != 0 && !is_valid_material(types[i], materials[i]) {
+            return false;
+        }
+
+        i += 1;
+    }
+
+    return true;
+}
+
+/// Returns the character counter of the material.
+pub fn material_amount(
+    character_materials: &mut CharacterMaterials,
+    material_type: u16,
+    material: u32
+) -> &mut u32 {
+    if material_type == (ResourceType::Basic as u16) {
+        return &mut character_materials.basic[(material - 1) as usize];
+    }
+
+    return &mut character_materials.raw[(material - 1) as usize];
+}
+
+/// Mints the withdrawn materials to the payer token account.
+pub fn mint_materials(accounts: &MaterialAccess, amount: u64, bump: u8) -> Result<()> {
+    let seeds: &[&[u8]] = &[MATERIAL_AUTHORITY_PREFIX.as_bytes(), &[bump]];
+    let signer = &[seeds];
+
+    let cpi_accounts = MintTo {
+        mint: accounts.material_mint.to_account_info(),
+        to: accounts.material_token_account.to_account_info(),
+        authority: accounts.material_authority.to_account_info(),
+    };
+
+    let cpi_context = CpiContext::new_with_signer(
+        accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer
+    );
+
+    return token::mint_to(cpi_context, amount);
+}
+
+/// Burns the deposited materials from the payer token account.
+pub fn burn_materials(accounts: &MaterialAccess, amount: u64) -> Result<()> {
+    let cpi_accounts = Burn {
+        mint: accounts.material_mint.to_account_info(),
+        from: accounts.material_token_account.to_account_info(),
+        authority: accounts.payer.to_account_info(),
+    };
+
+    let cpi_context = CpiContext::new(accounts.token_program.to_account_info(), cpi_accounts);
+
+    return token::burn(cpi_context, amount);
+}
+
+#[derive(Accounts)]
+#[instruction(material_type: u16, material: u32)]
+pub struct MaterialAccess<'info> {
+    #[account(mut,
+        constraint = is_mint_owner(character.mint, payer.key(), &character_token_account) @ ArisingError::InvalidOwner)]
+    payer: Signer<'info>,
+
+    #[account(
+        seeds = [CHARACTER_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character: Account<'info, Character>,
+
+    #[account(mut,
+        seeds = [CHARACTER_MATERIAL_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character_materials: Account<'info, CharacterMaterials>,
+
+    pub character_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = !is_paused(&config, Subsystem::Game) @ ArisingError::Paused)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [MATERIAL_MINT_PREFIX.as_bytes(), &material_type.to_le_bytes(), &material.to_le_bytes()],
+        bump)]
+    pub material_mint_registry: Account<'info, MaterialMint>,
+
+    #[account(mut,
+        address = material_mint_registry.mint @ MaterialError::InvalidMint)]
+    pub material_mint: Account<'info, Mint>,
+
+    #[account(mut,
+        associated_token::mint = material_mint,
+        associated_token::authority = payer)]
+    pub material_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: the PDA signing as the mint authority of the material mints.
+    #[account(seeds = [MATERIAL_AUTHORITY_PREFIX.as_bytes()], bump)]
+    pub material_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct TransferMaterials<'info> {
+    #[account(mut,
+        constraint = is_mint_owner(character.mint, payer.key(), &character_token_account) @ ArisingError::InvalidOwner)]
+    payer: Signer<'info>,
+
+    #[account(
+        seeds = [CHARACTER_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character: Account<'info, Character>,
+
+    #[account(mut,
+        seeds = [CHARACTER_MATERIAL_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character_materials: Account<'info, CharacterMaterials>,
+
+    pub character_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = !is_paused(&config, Subsystem::Game) @ ArisingError::Paused)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [CHARACTER_PREFIX.as_bytes(), &destination_character.mint.to_bytes()],
+        bump,
+        constraint = destination_character.key() != character.key() @ MaterialError::SameCharacter)]
+    pub destination_character: Account<'info, Character>,
+
+    #[account(mut,
+        seeds = [CHARACTER_MATERIAL_PREFIX.as_bytes(), &destination_character.mint.to_bytes()],
+        bump)]
+    pub destination_character_materials: Account<'info, CharacterMaterials>,
+}
+
+#[derive(Accounts)]
+#[instruction(material_type: u16, material: u32)]
+pub struct AddMaterialMint<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    #[account(
+        constraint = is_authorized(&config, authority.key(), &operator, Role::Economy) @ ArisingError::InvalidAuthority)]
+    authority: Signer<'info>,
+
+    pub config: Account<'info, Config>,
+
+    pub operator: Option<Account<'info, Operator>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [MATERIAL_MINT_PREFIX.as_bytes(), &material_type.to_le_bytes(), &material.to_le_bytes()],
+        bump,
+        space = MATERIAL_MINT_SIZE
+    )]
+    pub material_mint_registry: Account<'info, MaterialMint>,
+
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = 0,
+        mint::authority = material_authority
+    )]
+    pub material_mint: Account<'info, Mint>,
+
+    /// CHECK: the PDA signing as the mint authority of the material mints.
+    #[account(seeds = [MATERIAL_AUTHORITY_PREFIX.as_bytes()], bump)]
+    pub material_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// The size of a material mint registry entry.
+pub const MATERIAL_MINT_SIZE: usize = 8 + // discriminator
+    16 + // material_type
+    32 + // material
+    32; // mint
+
+/// Maps a raw or basic material to the token players withdraw it to.
+#[account]
+pub struct MaterialMint {
+    pub material_type: u16,
+    pub material: u32,
+    pub mint: Pubkey,
+}
+
+/// Emitted when materials move from one character to another.
+#[event]
+pub struct MaterialsTransferred {
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub material_type: u16,
+    pub material: u32,
+    pub amount: u32,
+}
@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::*;
+
+/// Unsigned integers the program does arithmetic on.
+pub trait CheckedMath: Sized + Copy {
+    fn add(self, other: Self) -> Option<Self>;
+    fn sub(self, other: Self) -> Option<Self>;
+    fn mul(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_checked_math {
+    ($($t:ty),*) => {
+        $(
+            impl CheckedMath for $t {
+                #[inline(always)]
+                fn add(self, other: Self) -> Option<Self> {
+                    return self.checked_add(other);
+                }
+
+                #[inline(always)]
+                fn sub(self, other: Self) -> Option<Self> {
+                    return self.checked_sub(other);
+                }
+
+                #[inline(always)]
+                fn mul(self, other: Self) -> Option<Self> {
+                    return self.checked_mul(other);
+                }
+            }
+        )*
+    };
+}
+
+impl_checked_math!(u16, u32, u64);
+
+/// Adds two values, failing with `MathOverflow` instead of panicking.
+#[inline(always)]
+pub fn checked_add<T: CheckedMath>(a: T, b: T) -> Result<T> {
+    return a.add(b).ok_or_else(|| ArisingError::MathOverflow.into());
+}
+
+/// Subtracts two values, failing with `MathUnderflow` instead of panicking.
+#[inline(always)]
+pub fn checked_sub<T: CheckedMath>(a: T, b: T) -> Result<T> {
+    return a.sub(b).ok_or_else(|| ArisingError::MathUnderflow.into());
+}
+
+/// Multiplies two values, failing with `MathOverflow` instead of panicking.
+#[inline(always)]
+pub fn checked_mul<T: CheckedMath>(a: T, b: T) -> Result<T> {
+    return a.mul(b).ok_or_else(|| ArisingError::MathOverflow.into());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn error_code<T>(result: Result<T>) -> u32 {
+        return match result {
+            Err(Error::AnchorError(error)) => error.error_code_number,
+            _ => panic!("expected an error"),
+        };
+    }
+
+    proptest! {
+        #[test]
+        fn add_matches_wide_math(a: u32, b: u32) {
+            let wide = (a as u64) + (b as u64);
+
+            match checked_add(a, b) {
+                Ok(value) => prop_assert_eq!(value as u64, wide),
+                Err(error) => {
+                    prop_assert!(wide > (u32::MAX as u64));
+                    prop_assert_eq!(error_code::<u32>(Err(error)), u32::from(ArisingError::MathOverflow));
+                }
+            }
+        }
+
+        #[test]
+        fn sub_matches_wide_math(a: u16, b: u16) {
+            let wide = (a as i32) - (b as i32);
+
+            match checked_sub(a, b) {
+                Ok(value) => prop_assert_eq!(value as i32, wide),
+                Err(error) => {
+                    prop_assert!(wide < 0);
+                    prop_assert_eq!(error_code::<u16>(Err(error)), u32::from(ArisingError::MathUnderflow));
+                }
+            }
+        }
+
+        #[test]
+        fn mul_matches_wide_math(a: u32, b: u32) {
+            let wide = (a as u64) * (b as u64);
+
+            match checked_mul(a, b) {
+                Ok(value) => prop_assert_eq!(value as u64, wide),
+                Err(_) => prop_assert!(wide > (u32::MAX as u64)),
+            }
+        }
+
+        #[test]
+        fn add_then_sub_roundtrips(a: u64, b: u64) {
+            if let Ok(sum) = checked_add(a, b) {
+                prop_assert_eq!(checked_sub(sum, b).unwrap(), a);
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+
+use crate::config::*;
+use crate::errors::*;
+
+pub const OPERATOR_PREFIX: &str = "arising_operator";
+
+/// Capabilities the authority can delegate to an operator, stored as bitflags.
+pub enum Role {
+    Content = 1,
+    Pause = 2,
+    Characters = 4,
+    Economy = 8,
+}
+
+/// Every role an operator can hold.
+pub const ALL_ROLES: u16 = (Role::Content as u16) |
+    (Role::Pause as u16) |
+    (Role::Characters as u16) |
+    (Role::Economy as u16);
+
+/// Checks the signer is the program authority or an operator holding the role.
+pub fn is_authorized(
+    config: &Config,
+    signer: Pubkey,
+    operator: &Option<Account<Operator>>,
+    role: Role
+) -> bool {
+    if signer == config.authority {
+        return true;
+    }
+
+    return match operator {
+        Some(operator) => operator.operator == signer && (operator.roles & (role as u16)) != 0,
+        None => false,
+    };
+}
+
+#[derive(Accounts)]
+#[instruction(operator_key: Pubkey)]
+pub struct AddOperator<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    authority: Signer<'info>,
+
+    #[account(has_one = authority @ ArisingError::InvalidAuthority)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [OPERATOR_PREFIX.as_bytes(), &operator_key.to_bytes()],
+        bump,
+        space = OPERATOR_SIZE
+    )]
+    pub operator: Account<'info, Operator>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateOperator<'info> {
+    authority: Signer<'info>,
+
+    #[account(has_one = authority @ ArisingError::InvalidAuthority)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut,
+        seeds = [OPERATOR_PREFIX.as_bytes(), &operator.operator.to_bytes()],
+        bump)]
+    pub operator: Account<'info, Operator>,
+}
+
+/// The size of an operator.
+pub const OPERATOR_SIZE: usize = 8 + // discriminator
+    32 + // operator
+    16; // roles
+
+/// The roles delegated to an operator key, a role-less operator has no access.
+#[account]
+pub struct Operator {
+    pub operator: Pubkey,
+    pub roles: u16,
+}
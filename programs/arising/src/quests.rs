@@ -1,19 +1,73 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{ prelude::*, solana_program::sysvar::slot_hashes };
 use anchor_spl::token::TokenAccount;
 
 use crate::characters::*;
 use crate::codex::*;
 use crate::config::*;
 use crate::errors::*;
+use crate::materials::*;
+use crate::math::*;
+use crate::operators::*;
+use crate::random::*;
 
 const QUESTS_PREFIX: &str = "arising_quest";
 
+/// Checks every rewarded material is a known raw material.
+#[inline(always)]
+pub fn is_valid_quest_reward(quest: &Quest) -> bool {
+    let mut i: usize = 0;
+
+    loop {
+        if i >= 10 {
+            break;
+        }
+
+        let material = quest.materials_reward[i];
+
+        if material != 0 && !is_valid_material(ResourceType::Raw as u16, material) {
+            return false;
+        }
+
+        i += 1;
+    }
+
+    return true;
+}
+
 #[inline(always)]
 pub fn quest_rewards(
-    character: &mut Account<Character>,
+    character_materials: &mut Account<CharacterMaterials>,
+    materials: &[u32; 10],
+    amounts: &[u32; 10],
+) -> Result<()> {
+    let mut i: usize = 0;
+    loop {
+        if i >= 10 {
+            break;
+        }
+
+        let material = materials[i];
+        let amount = amounts[i];
+
+        if material != 0 {
+            let index = (material - 1) as usize;
+            character_materials.raw[index] = checked_add(character_materials.raw[index], amount)?;
+        }
+
+        i += 1;
+    }
+
+    return Ok(());
+}
+
+/// Rewards a won raid, each material amount is rolled between half and the full amount.
+#[inline(always)]
+pub fn raid_rewards(
+    character_materials: &mut Account<CharacterMaterials>,
     materials: &[u32; 10],
     amounts: &[u32; 10],
-) {
+    rng: &mut Rng,
+) -> Result<()> {
     let mut i: usize = 0;
     loop {
         if i >= 10 {
@@ -24,11 +78,15 @@ pub fn quest_rewards(
         let amount = amounts[i];
 
         if material != 0 {
-            character.raw[(material - 1) as usize] += amount;
+            let rolled = amount / 2 + (rng.roll((amount - amount / 2 + 1) as u64) as u32);
+            let index = (material - 1) as usize;
+            character_materials.raw[index] = checked_add(character_materials.raw[index], rolled)?;
         }
 
         i += 1;
     }
+
+    return Ok(());
 }
 
 #[derive(Accounts)]
@@ -37,25 +95,52 @@ pub struct QuestAccess<'info> {
         constraint = is_mint_owner(character.mint, payer.key(), &character_token_account) @ ArisingError::InvalidOwner)]
     payer: Signer<'info>,
 
-    #[account(mut)]
+    #[account(mut,
+        seeds = [CHARACTER_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
     pub character: Account<'info, Character>,
 
+    #[account(mut,
+        seeds = [CHARACTER_MATERIAL_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character_materials: Account<'info, CharacterMaterials>,
+
+    #[account(mut,
+        seeds = [CHARACTER_SLOTS_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character_slots: Account<'info, CharacterSlots>,
+
+    #[account(
+        seeds = [CHARACTER_EQUIPMENT_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character_equipment: Account<'info, CharacterEquipment>,
+
     #[account(mut)]
     pub character_token_account: Account<'info, TokenAccount>,
 
+    #[account(
+        constraint = !is_paused(&config, Subsystem::Quests) @ ArisingError::Paused)]
+    pub config: Account<'info, Config>,
+
     #[account(mut)]
     pub quest: Account<'info, Quest>,
+
+    /// CHECK: the address is checked to be the SlotHashes sysvar and the data is parsed on claim.
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
 pub struct UpdateQuest<'info> {
-    #[account(mut,
-        constraint = payer.key() == config.authority @ ArisingError::InvalidAuthority)]
-    payer: Signer<'info>,
+    #[account(
+        constraint = is_authorized(&config, authority.key(), &operator, Role::Content) @ ArisingError::InvalidAuthority)]
+    authority: Signer<'info>,
 
     #[account(mut)]
     pub config: Account<'info, Config>,
 
+    pub operator: Option<Account<'info, Operator>>,
+
     #[account(mut)]
     pub quest: Account<'info, Quest>,
 }
@@ -64,14 +149,19 @@ pub struct UpdateQuest<'info> {
 #[instruction(bump: u8, id: u32)]
 pub struct AddQuest<'info> {
     #[account(mut,
-        constraint = payer.key() == config.authority @ ArisingError::InvalidAuthority,
         constraint = (config.quests + 1) == (id as u64) @ QuestError::InvalidID
     )]
     payer: Signer<'info>,
 
+    #[account(
+        constraint = is_authorized(&config, authority.key(), &operator, Role::Content) @ ArisingError::InvalidAuthority)]
+    authority: Signer<'info>,
+
     #[account(mut)]
     pub config: Account<'info, Config>,
 
+    pub operator: Option<Account<'info, Operator>>,
+
     #[account(
         init,
         payer = payer,
@@ -0,0 +1,181 @@
+use anchor_lang::solana_program::hash::hashv;
+
+/// Returns the commitment a client stores when starting a task for a secret it reveals on claim.
+#[inline(always)]
+pub fn commitment(reveal: &[u8; 32]) -> [u8; 32] {
+    return hashv(&[reveal]).to_bytes();
+}
+
+#[inline(always)]
+pub fn is_valid_reveal(commitment_hash: &[u8; 32], reveal: &[u8; 32]) -> bool {
+    return commitment(reveal) == *commitment_hash;
+}
+
+/// The hash of the first slot after a commitment, looked up in the SlotHashes sysvar.
+#[derive(Debug, PartialEq)]
+pub enum SlotHashLookup {
+    /// No slot after the commitment has a hash yet.
+    Pending,
+    /// The slot after the commitment aged out of the sysvar.
+    Expired,
+    Found([u8; 32]),
+}
+
+/// Finds the hash of the first slot after `commitment_slot` in the SlotHashes sysvar account data.
+/// The data is a bincode serialized `Vec<(u64, [u8; 32])>` sorted from the newest slot, so the
+/// result doesn't depend on the slot the lookup happens in.
+pub fn slot_hash_after(data: &[u8], commitment_slot: u64) -> SlotHashLookup {
+    if data.len() < 8 {
+        return SlotHashLookup::Pending;
+    }
+
+    let entries = u64::from_le_bytes(data[0..8].try_into().unwrap()) as usize;
+
+    let mut found: Option<(u64, [u8; 32])> = None;
+    let mut reached_commitment = false;
+    let mut i: usize = 0;
+
+    loop {
+        let start = 8 + i * 40;
+
+        if i >= entries || data.len() < start + 40 {
+            break;
+        }
+
+        let slot = u64::from_le_bytes(data[start..start + 8].try_into().unwrap());
+
+        if slot <= commitment_slot {
+            reached_commitment = true;
+            break;
+        }
+
+        found = Some((slot, data[start + 8..start + 40].try_into().unwrap()));
+
+        i += 1;
+    }
+
+    return match found {
+        None => SlotHashLookup::Pending,
+        // Skipped slots have no hash, the oldest entry only proves it when the commitment is in sight
+        Some((slot, hash)) if reached_commitment || slot == commitment_slot + 1 => {
+            SlotHashLookup::Found(hash)
+        }
+        Some(_) => SlotHashLookup::Expired,
+    };
+}
+
+/// Mixes the revealed secret with a slot hash unknown at commitment time.
+#[inline(always)]
+pub fn mix_entropy(reveal: &[u8; 32], slot_hash: &[u8; 32]) -> [u8; 32] {
+    return hashv(&[reveal, slot_hash]).to_bytes();
+}
+
+/// Deterministic number generator seeded from the mixed entropy.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(entropy: &[u8; 32]) -> Rng {
+        let mut state: u64 = 0;
+        let mut i: usize = 0;
+
+        loop {
+            if i >= 4 {
+                break;
+            }
+
+            state ^= u64::from_le_bytes(entropy[i * 8..(i + 1) * 8].try_into().unwrap());
+
+            i += 1;
+        }
+
+        return Rng { state };
+    }
+
+    /// Returns the next number of the splitmix64 sequence.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+
+        return z ^ (z >> 31);
+    }
+
+    /// Returns a number between 0 and `max` (exclusive), or 0 when `max` is 0.
+    pub fn roll(&mut self, max: u64) -> u64 {
+        if max == 0 {
+            return 0;
+        }
+
+        return self.next_u64() % max;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reveal_must_match_commitment() {
+        let reveal = [7u8; 32];
+        let stored = commitment(&reveal);
+
+        assert!(is_valid_reveal(&stored, &reveal));
+        assert!(!is_valid_reveal(&stored, &[8u8; 32]));
+    }
+
+    fn slot_hashes(entries: &[(u64, u8)]) -> Vec<u8> {
+        let mut data = vec![];
+        data.extend_from_slice(&(entries.len() as u64).to_le_bytes());
+
+        let mut i = 0;
+        while i < entries.len() {
+            data.extend_from_slice(&entries[i].0.to_le_bytes());
+            data.extend_from_slice(&[entries[i].1; 32]);
+            i += 1;
+        }
+
+        return data;
+    }
+
+    #[test]
+    fn reads_the_slot_hash_after_the_commitment() {
+        let early = slot_hashes(&[(42, 1), (41, 2), (39, 3), (38, 4)]);
+        let late = slot_hashes(&[(50, 5), (45, 6), (42, 1), (41, 2), (39, 3), (38, 4)]);
+
+        // Skipped slots are ignored and newer slots don't change the result
+        assert_eq!(slot_hash_after(&early, 38), SlotHashLookup::Found([3u8; 32]));
+        assert_eq!(slot_hash_after(&late, 38), SlotHashLookup::Found([3u8; 32]));
+        assert_eq!(slot_hash_after(&early, 41), SlotHashLookup::Found([1u8; 32]));
+
+        assert_eq!(slot_hash_after(&early, 42), SlotHashLookup::Pending);
+        assert_eq!(slot_hash_after(&0u64.to_le_bytes(), 42), SlotHashLookup::Pending);
+
+        // The slot after the commitment is known to be the oldest entry
+        assert_eq!(slot_hash_after(&early, 37), SlotHashLookup::Found([4u8; 32]));
+
+        // Nothing proves the oldest entry follows the commitment
+        assert_eq!(slot_hash_after(&late, 30), SlotHashLookup::Expired);
+    }
+
+    #[test]
+    fn rolls_are_deterministic_and_bounded() {
+        let entropy = mix_entropy(&[3u8; 32], &[4u8; 32]);
+
+        let mut first = Rng::new(&entropy);
+        let mut second = Rng::new(&entropy);
+
+        let mut i = 0;
+        while i < 100 {
+            let roll = first.roll(10);
+            assert!(roll < 10);
+            assert_eq!(roll, second.roll(10));
+            i += 1;
+        }
+
+        assert_eq!(first.roll(0), 0);
+    }
+}
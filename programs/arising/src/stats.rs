@@ -0,0 +1,260 @@
+use anchor_lang::prelude::*;
+
+use crate::characters::*;
+use crate::codex::*;
+use crate::errors::*;
+use crate::items::*;
+
+/// Every few levels the character gets a point of physical and magical defense.
+pub const LEVELS_PER_DEFENSE: u16 = 10;
+
+/// The stats and attributes of a character after applying its level and equipment.
+#[derive(Clone, Default, AnchorSerialize, AnchorDeserialize)]
+pub struct EffectiveStats {
+    pub stats: BaseStats,
+    pub attributes: BaseAttributes,
+}
+
+#[inline(always)]
+fn add_stats(target: &mut BaseStats, stats: &BaseStats) {
+    target.might = target.might.saturating_add(stats.might);
+    target.speed = target.speed.saturating_add(stats.speed);
+    target.intellect = target.intellect.saturating_add(stats.intellect);
+}
+
+#[inline(always)]
+fn remove_stats(target: &mut BaseStats, stats: &BaseStats) {
+    target.might = target.might.saturating_sub(stats.might);
+    target.speed = target.speed.saturating_sub(stats.speed);
+    target.intellect = target.intellect.saturating_sub(stats.intellect);
+}
+
+#[inline(always)]
+fn add_attributes(target: &mut BaseAttributes, attributes: &BaseAttributes) {
+    target.atk = target.atk.saturating_add(attributes.atk);
+    target.def = target.def.saturating_add(attributes.def);
+    target.range = target.range.saturating_add(attributes.range);
+    target.mag_atk = target.mag_atk.saturating_add(attributes.mag_atk);
+    target.mag_def = target.mag_def.saturating_add(attributes.mag_def);
+    target.rate = target.rate.saturating_add(attributes.rate);
+}
+
+#[inline(always)]
+fn remove_attributes(target: &mut BaseAttributes, attributes: &BaseAttributes) {
+    target.atk = target.atk.saturating_sub(attributes.atk);
+    target.def = target.def.saturating_sub(attributes.def);
+    target.range = target.range.saturating_sub(attributes.range);
+    target.mag_atk = target.mag_atk.saturating_sub(attributes.mag_atk);
+    target.mag_def = target.mag_def.saturating_sub(attributes.mag_def);
+    target.rate = target.rate.saturating_sub(attributes.rate);
+}
+
+/// Computes the character stats from its base stats, level and equipped items.
+/// All the additions are applied before the reductions, and values never go below 0.
+pub fn effective_stats(
+    base_stats: &BaseStats,
+    level: u16,
+    items: &[(ItemInstance, ItemDefinition)]
+) -> EffectiveStats {
+    let mut stats = base_stats.clone();
+
+    let mut attributes = BaseAttributes {
+        def: level / LEVELS_PER_DEFENSE,
+        mag_def: level / LEVELS_PER_DEFENSE,
+        ..BaseAttributes::default()
+    };
+
+    for (item, definition) in items {
+        add_stats(&mut stats, &definition.stats_additions);
+        add_stats(&mut stats, &item.stats_bonus);
+        add_attributes(&mut attributes, &definition.attributes_additions);
+        add_attributes(&mut attributes, &item.attributes_bonus);
+    }
+
+    for (_, definition) in items {
+        remove_stats(&mut stats, &definition.stats_reductions);
+        remove_attributes(&mut attributes, &definition.attributes_reductions);
+    }
+
+    return EffectiveStats { stats, attributes };
+}
+
+/// Returns the items in the equipment, once each even when they take more than one slot.
+pub fn equipped_keys(equipment: &CharacterEquipment) -> Vec<Pubkey> {
+    let slots = [
+        equipment.helmet,
+        equipment.shoulder_guards,
+        equipment.arm_guards,
+        equipment.hands,
+        equipment.rings,
+        equipment.necklace,
+        equipment.chest,
+        equipment.legs,
+        equipment.belt,
+        equipment.feet,
+        equipment.cape,
+        equipment.left_hand,
+        equipment.right_hand,
+    ];
+
+    let mut keys: Vec<Pubkey> = vec![];
+
+    for key in slots {
+        if key != Pubkey::default() && !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    return keys;
+}
+
+/// Loads the equipped items from `(item, item_definition)` account pairs,
+/// given in the same order as `equipped_keys`.
+pub fn load_equipped_items(
+    equipment: &CharacterEquipment,
+    accounts: &[AccountInfo]
+) -> Result<Vec<(ItemInstance, ItemDefinition)>> {
+    let keys = equipped_keys(equipment);
+
+    if accounts.len() != keys.len() * 2 {
+        return Err(ItemError::InvalidEquippedAccounts.into());
+    }
+
+    let mut items: Vec<(ItemInstance, ItemDefinition)> = vec![];
+    let mut i: usize = 0;
+
+    loop {
+        if i >= keys.len() {
+            break;
+        }
+
+        let item_info = &accounts[i * 2];
+        if item_info.key() != keys[i] {
+            return Err(ItemError::InvalidEquippedAccounts.into());
+        }
+
+        let definition_info = &accounts[i * 2 + 1];
+        if *item_info.owner != crate::ID || *definition_info.owner != crate::ID {
+            return Err(ItemError::InvalidEquippedAccounts.into());
+        }
+
+        let item = ItemInstance::try_deserialize(&mut &item_info.try_borrow_data()?[..])?;
+        let definition = ItemDefinition::try_deserialize(
+            &mut &definition_info.try_borrow_data()?[..]
+        )?;
+
+        if definition.id != item.id {
+            return Err(ItemError::InvalidEquippedAccounts.into());
+        }
+
+        items.push((item, definition));
+
+        i += 1;
+    }
+
+    return Ok(items);
+}
+
+#[derive(Accounts)]
+pub struct CharacterStatsView<'info> {
+    #[account(
+        seeds = [CHARACTER_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character: Account<'info, Character>,
+
+    #[account(
+        seeds = [CHARACTER_EQUIPMENT_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character_equipment: Account<'info, CharacterEquipment>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: u32) -> (ItemInstance, ItemDefinition) {
+        let item = ItemInstance {
+            id,
+            mint: Pubkey::default(),
+            tier: 0,
+            durability: MAX_DURABILITY,
+            stats_bonus: BaseStats::default(),
+            attributes_bonus: BaseAttributes::default(),
+            upgrading: false,
+            equipped: true,
+        };
+
+        let definition = ItemDefinition {
+            id,
+            name: "".to_string(),
+            uri: "".to_string(),
+            item_type: ItemType::OneHanded as u16,
+            stats_additions: BaseStats::default(),
+            attributes_additions: BaseAttributes::default(),
+            stats_reductions: BaseStats::default(),
+            attributes_reductions: BaseAttributes::default(),
+            level_required: 0,
+        };
+
+        return (item, definition);
+    }
+
+    #[test]
+    fn base_stats_without_equipment() {
+        let base = BaseStats { might: 3, speed: 2, intellect: 1 };
+
+        let result = effective_stats(&base, 25, &[]);
+
+        assert_eq!(result.stats.might, 3);
+        assert_eq!(result.stats.speed, 2);
+        assert_eq!(result.stats.intellect, 1);
+        assert_eq!(result.attributes.def, 2);
+        assert_eq!(result.attributes.mag_def, 2);
+        assert_eq!(result.attributes.atk, 0);
+    }
+
+    #[test]
+    fn equipment_adds_and_reduces() {
+        let base = BaseStats { might: 1, speed: 1, intellect: 1 };
+
+        let mut sword = item(1);
+        sword.1.stats_additions.might = 2;
+        sword.1.attributes_additions.atk = 4;
+        sword.1.stats_reductions.speed = 1;
+        sword.0.attributes_bonus.atk = 1;
+
+        let mut armor = item(2);
+        armor.1.attributes_additions.def = 3;
+        armor.1.stats_reductions.speed = 1;
+
+        let result = effective_stats(&base, 0, &[sword, armor]);
+
+        assert_eq!(result.stats.might, 3);
+        assert_eq!(result.stats.speed, 0);
+        assert_eq!(result.attributes.atk, 5);
+        assert_eq!(result.attributes.def, 3);
+    }
+
+    #[test]
+    fn two_handed_items_count_once() {
+        let item = Pubkey::new_unique();
+
+        let equipment = CharacterEquipment {
+            helmet: Pubkey::default(),
+            shoulder_guards: Pubkey::default(),
+            arm_guards: Pubkey::default(),
+            hands: Pubkey::default(),
+            rings: Pubkey::default(),
+            necklace: Pubkey::default(),
+            chest: Pubkey::default(),
+            legs: Pubkey::default(),
+            belt: Pubkey::default(),
+            feet: Pubkey::default(),
+            cape: Pubkey::default(),
+            left_hand: item,
+            right_hand: item,
+        };
+
+        assert_eq!(equipped_keys(&equipment), vec![item]);
+    }
+}
@@ -0,0 +1,199 @@
+use anchor_lang::{ prelude::*, solana_program::sysvar::slot_hashes };
+use anchor_spl::token::{ Mint, Token, TokenAccount };
+
+use crate::characters::*;
+use crate::codex::*;
+use crate::config::*;
+use crate::errors::*;
+use crate::items::*;
+use crate::math::*;
+use crate::operators::*;
+use crate::random::*;
+
+const UPGRADE_RECIPE_PREFIX: &str = "arising_upgrade_recipe";
+
+/// The success rate of an upgrade is expressed over this value, 10000 always succeeds.
+pub const UPGRADE_RATE_PRECISION: u16 = 10000;
+
+/// Checks the recipe applies to the item definition and its current tier.
+#[inline(always)]
+pub fn can_upgrade_item(item: &ItemInstance, recipe: &UpgradeRecipe) -> bool {
+    return (recipe.item_id == 0 || recipe.item_id == item.id) && recipe.tier_required == item.tier;
+}
+
+#[inline(always)]
+pub fn is_upgrade_successful(success_rate: u16, rng: &mut Rng) -> bool {
+    return rng.roll(UPGRADE_RATE_PRECISION as u64) < (success_rate as u64);
+}
+
+/// Raises the item tier and adds the recipe bonuses to the item.
+pub fn upgrade_item(item: &mut Account<ItemInstance>, recipe: &UpgradeRecipe) -> Result<()> {
+    item.tier = checked_add(item.tier, 1)?;
+
+    let stats = &mut item.stats_bonus;
+    stats.might = checked_add(stats.might, recipe.stats_bonus.might)?;
+    stats.speed = checked_add(stats.speed, recipe.stats_bonus.speed)?;
+    stats.intellect = checked_add(stats.intellect, recipe.stats_bonus.intellect)?;
+
+    let attributes = &mut item.attributes_bonus;
+    attributes.atk = checked_add(attributes.atk, recipe.attributes_bonus.atk)?;
+    attributes.def = checked_add(attributes.def, recipe.attributes_bonus.def)?;
+    attributes.range = checked_add(attributes.range, recipe.attributes_bonus.range)?;
+    attributes.mag_atk = checked_add(attributes.mag_atk, recipe.attributes_bonus.mag_atk)?;
+    attributes.mag_def = checked_add(attributes.mag_def, recipe.attributes_bonus.mag_def)?;
+    attributes.rate = checked_add(attributes.rate, recipe.attributes_bonus.rate)?;
+
+    return Ok(());
+}
+
+#[derive(Accounts)]
+pub struct UpgradeAccess<'info> {
+    #[account(mut,
+        constraint = is_mint_owner(character.mint, payer.key(), &character_token_account) @ ArisingError::InvalidOwner)]
+    payer: Signer<'info>,
+
+    #[account(mut,
+        seeds = [CHARACTER_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character: Account<'info, Character>,
+
+    #[account(mut,
+        seeds = [CHARACTER_MATERIAL_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character_materials: Account<'info, CharacterMaterials>,
+
+    #[account(mut,
+        seeds = [CHARACTER_SLOTS_PREFIX.as_bytes(), &character.mint.to_bytes()],
+        bump)]
+    pub character_slots: Account<'info, CharacterSlots>,
+
+    #[account(mut)]
+    pub character_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        constraint = !is_paused(&config, Subsystem::Game) @ ArisingError::Paused)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub upgrade_recipe: Account<'info, UpgradeRecipe>,
+
+    #[account(mut,
+        seeds = [ITEM_PREFIX.as_bytes(), &item.mint.to_bytes()],
+        bump)]
+    pub item: Account<'info, ItemInstance>,
+
+    #[account(address = item.mint)]
+    pub item_mint: Account<'info, Mint>,
+
+    #[account(mut,
+        constraint = is_mint_owner(item.mint, payer.key(), &item_token_account) @ ItemError::NotItemOwner)]
+    pub item_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: the PDA signing as the freeze authority of the item mints.
+    #[account(seeds = [ITEM_AUTHORITY_PREFIX.as_bytes()], bump)]
+    pub item_authority: UncheckedAccount<'info>,
+
+    /// CHECK: the address is checked to be the SlotHashes sysvar and the data is parsed on claim.
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateUpgradeRecipe<'info> {
+    #[account(
+        constraint = is_authorized(&config, authority.key(), &operator, Role::Content) @ ArisingError::InvalidAuthority)]
+    authority: Signer<'info>,
+
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+
+    pub operator: Option<Account<'info, Operator>>,
+
+    #[account(mut)]
+    pub upgrade_recipe: Account<'info, UpgradeRecipe>,
+}
+
+#[derive(Accounts)]
+#[instruction(bump: u8, id: u32)]
+pub struct AddUpgradeRecipe<'info> {
+    #[account(mut,
+        constraint = (config.upgrade_recipes + 1) == (id as u64) @ UpgradeError::InvalidID
+    )]
+    payer: Signer<'info>,
+
+    #[account(
+        constraint = is_authorized(&config, authority.key(), &operator, Role::Content) @ ArisingError::InvalidAuthority)]
+    authority: Signer<'info>,
+
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+
+    pub operator: Option<Account<'info, Operator>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [UPGRADE_RECIPE_PREFIX.as_bytes(), &id.to_le_bytes()],
+        bump,
+        space = UPGRADE_RECIPE_SIZE
+    )]
+    pub upgrade_recipe: Account<'info, UpgradeRecipe>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// The size of an upgrade recipe.
+pub const UPGRADE_RECIPE_SIZE: usize = 8 + // discriminator
+    32 + // id
+    24 + // name
+    32 + // item_id
+    16 + // tier_required
+    320 + // materials
+    320 + // materials_amounts
+    150 + // materials_types
+    BASE_STATS_SIZE + // stats_required
+    32 + // cooldown
+    16 + // level_required
+    16 + // success_rate
+    BASE_STATS_SIZE + // stats_bonus
+    BASE_ATTRIBUTES_SIZE + // attributes_bonus
+    1; // available
+
+/// The full metadata information for an upgrade recipe.
+/// An `item_id` of 0 applies the recipe to any item of the required tier.
+#[account]
+pub struct UpgradeRecipe {
+    pub id: u32,
+    pub name: String,
+    pub item_id: u32,
+    pub tier_required: u16,
+    pub materials: [u32; 10],
+    pub materials_amounts: [u32; 10],
+    pub materials_types: [u16; 10],
+    pub stats_required: BaseStats,
+    pub cooldown: u32,
+    pub level_required: u16,
+    pub success_rate: u16,
+    pub stats_bonus: BaseStats,
+    pub attributes_bonus: BaseAttributes,
+    pub available: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn success_rate_bounds() {
+        let mut rng = Rng::new(&[5u8; 32]);
+
+        let mut i = 0;
+        while i < 100 {
+            assert!(is_upgrade_successful(UPGRADE_RATE_PRECISION, &mut rng));
+            assert!(!is_upgrade_successful(0, &mut rng));
+            i += 1;
+        }
+    }
+}
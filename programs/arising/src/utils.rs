@@ -2,4 +2,8 @@ use anchor_lang::{ prelude::*, solana_program::clock };
 
 pub fn now() -> u64 {
     return clock::Clock::get().unwrap().unix_timestamp.try_into().unwrap();
+}
+
+pub fn current_slot() -> u64 {
+    return clock::Clock::get().unwrap().slot;
 }
\ No newline at end of file
@@ -0,0 +1,180 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token::spl_token;
+use ::arising::*;
+use common::*;
+use solana_program_test::*;
+use solana_sdk::signature::{ Keypair, Signer };
+
+async fn set_respec(
+    context: &mut ProgramTestContext,
+    mint: Pubkey,
+    cost: u64,
+    seconds: u64
+) -> std::result::Result<(), BanksClientError> {
+    let accounts = ::arising::accounts::SetRespec {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        respec_mint: mint,
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::SetRespec {
+        respec_cost: cost,
+        seconds_between_respecs: seconds,
+    });
+
+    return process(context, &[ix], &[]).await;
+}
+
+async fn respec_character(
+    context: &mut ProgramTestContext,
+    character: &CharacterAccounts,
+    mint: Pubkey,
+    token_account: Pubkey
+) -> std::result::Result<(), BanksClientError> {
+    let accounts = ::arising::accounts::CharacterRespec {
+        payer: context.payer.pubkey(),
+        character: character.character,
+        config: config_address(),
+        character_token_account: character.token_account,
+        respec_mint: mint,
+        respec_token_account: token_account,
+        token_program: spl_token::ID,
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::RespecCharacter {});
+
+    return process(context, &[ix], &[]).await;
+}
+
+#[tokio::test]
+async fn assigned_points_are_spent() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let character = add_character(&mut context).await;
+
+    assign_stats(&mut context, &character, BaseStats { might: 3, speed: 0, intellect: 0 }).await
+        .unwrap();
+    assign_stats(&mut context, &character, BaseStats { might: 0, speed: 2, intellect: 1 }).await
+        .unwrap();
+
+    let stats: Character = fetch(&mut context, character.character).await;
+    assert_eq!(stats.base_stats.might, 3);
+    assert_eq!(stats.base_stats.speed, 2);
+    assert_eq!(stats.base_stats.intellect, 1);
+
+    // Every starting point is spent, assigning again can't inflate the stats
+    let result = assign_stats(&mut context, &character, BaseStats {
+        might: 1,
+        speed: 0,
+        intellect: 0,
+    }).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::NotEnoughAssignablePoints));
+
+    let stats: Character = fetch(&mut context, character.character).await;
+    assert_eq!(stats.base_stats.might, 3);
+}
+
+#[tokio::test]
+async fn respec_refunds_the_assigned_points() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let (mint, token_account) = mint_tokens(&mut context, 5).await;
+    set_respec(&mut context, mint, 2, 100).await.unwrap();
+
+    let character = add_character(&mut context).await;
+    assign_stats(&mut context, &character, BaseStats { might: 6, speed: 0, intellect: 0 }).await
+        .unwrap();
+
+    respec_character(&mut context, &character, mint, token_account).await.unwrap();
+
+    let stats: Character = fetch(&mut context, character.character).await;
+    assert_eq!(stats.base_stats.might, 0);
+    assert_eq!(stats.pool_stats.might, 0);
+
+    let token = fetch_token_account(&mut context, token_account).await;
+    assert_eq!(token.amount, 3);
+
+    // All the points can be assigned again
+    assign_stats(&mut context, &character, BaseStats { might: 2, speed: 2, intellect: 2 }).await
+        .unwrap();
+
+    let result = respec_character(&mut context, &character, mint, token_account).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::RespecNotAvailable));
+
+    warp_seconds(&mut context, 200).await;
+    respec_character(&mut context, &character, mint, token_account).await.unwrap();
+
+    warp_seconds(&mut context, 200).await;
+
+    let result = respec_character(&mut context, &character, mint, token_account).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::NotEnoughRespecTokens));
+}
+
+#[tokio::test]
+async fn respec_settings_are_bounded() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let (mint, _) = mint_tokens(&mut context, 5).await;
+
+    let result = set_respec(&mut context, mint, MAX_RESPEC_COST + 1, 100).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidRespecCost));
+
+    let result = set_respec(&mut context, mint, 2, u64::MAX).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidRespecInterval));
+
+    set_respec(&mut context, mint, MAX_RESPEC_COST, MAX_SECONDS_BETWEEN_RESPECS).await.unwrap();
+
+    let config: Config = fetch(&mut context, config_address()).await;
+    assert_eq!(config.seconds_between_respecs, MAX_SECONDS_BETWEEN_RESPECS);
+}
+
+#[tokio::test]
+async fn characters_belong_to_the_collection() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let config: Config = fetch(&mut context, config_address()).await;
+    assert_eq!(config.character_collection, collection_mint().pubkey());
+    assert_eq!(config.character_creator, context.payer.pubkey());
+
+    let character = mint_collection_character(&mut context, false).await;
+    let ix = add_character_instruction(&context, &character);
+    let result = process(&mut context, &[ix], &[]).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::InvalidCharacterCollection));
+
+    // Fungible tokens with a metadata aren't characters
+    let (mint, token_account) = mint_tokens(&mut context, 5).await;
+    set_metadata(&mut context, &mint, None).await;
+
+    let ix = add_character_instruction(&context, &character_accounts(mint, token_account));
+    let result = process(&mut context, &[ix], &[]).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::InvalidCharacterMint));
+
+    // Only NFTs verified by the configured creator are accepted
+    let accounts = ::arising::accounts::SetCharacterCollection {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        collection_mint: collection_mint().pubkey(),
+    };
+    let ix = instruction(accounts, ::arising::instruction::SetCharacterCollection {
+        creator: Keypair::new().pubkey(),
+    });
+    process(&mut context, &[ix], &[]).await.unwrap();
+
+    let character = mint_character(&mut context).await;
+    let ix = add_character_instruction(&context, &character);
+    let result = process(&mut context, &[ix], &[]).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::InvalidCharacterCreator));
+}
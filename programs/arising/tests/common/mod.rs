@@ -0,0 +1,851 @@
+#![allow(dead_code)]
+
+use anchor_lang::{ prelude::*, InstructionData, ToAccountMetas };
+use anchor_spl::{ associated_token::get_associated_token_address, token::spl_token };
+use ::arising::*;
+use mpl_token_metadata::state::{ Collection, Creator, Data, Key, Metadata, MAX_METADATA_LEN };
+use solana_program_test::*;
+use solana_sdk::{
+    hash::Hash,
+    instruction::{ Instruction, InstructionError },
+    program_pack::Pack,
+    account::AccountSharedData,
+    signature::{ keypair_from_seed, Keypair, Signer },
+    slot_hashes::SlotHashes,
+    system_instruction,
+    system_program,
+    sysvar,
+    transaction::{ Transaction, TransactionError },
+};
+
+pub const CONFIG_PREFIX: &str = "arising_config_account";
+pub const FORGE_RECIPE_PREFIX: &str = "arising_forge_recipe";
+pub const QUESTS_PREFIX: &str = "arising_quest";
+pub const CRAFT_RECIPE_PREFIX: &str = "arising_craft_recipe";
+pub const ITEM_PREFIX: &str = "arising_item";
+pub const ITEM_DEFINITION_PREFIX: &str = "arising_item_definition";
+pub const ITEM_AUTHORITY_PREFIX: &str = "arising_item_authority";
+pub const METADATA_PREFIX: &str = "metadata";
+
+pub const WOOD: u32 = 1;
+pub const GOLD: u32 = 12;
+pub const WOOD_PLANK: u32 = 1;
+pub const BONE_DAGGER: u32 = 1;
+
+/// All the program accounts of a character.
+pub struct CharacterAccounts {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub character: Pubkey,
+    pub character_materials: Pubkey,
+    pub character_slots: Pubkey,
+    pub character_equipment: Pubkey,
+}
+
+pub fn program_test() -> ProgramTest {
+    let mut program_test = ProgramTest::new("arising", ::arising::ID, processor!(::arising::entry));
+
+    // Run the token program natively alongside the game program
+    program_test.add_program(
+        "spl_token",
+        spl_token::ID,
+        processor!(spl_token::processor::Processor::process)
+    );
+
+    program_test.add_program(
+        "spl_associated_token_account",
+        spl_associated_token_account::ID,
+        processor!(spl_associated_token_account::processor::process_instruction)
+    );
+
+    program_test.add_program(
+        "mpl_token_metadata",
+        mpl_token_metadata::ID,
+        processor!(process_token_metadata)
+    );
+
+    return program_test;
+}
+
+/// Runs the token metadata program natively.
+fn process_token_metadata(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8]
+) -> solana_sdk::entrypoint::ProgramResult {
+    // The token metadata processor ties every lifetime to the program id one,
+    // the accounts outlive the call so they can be widened for its duration.
+    let program_id: &'static Pubkey = Box::leak(Box::new(*program_id));
+    let accounts: &'static [AccountInfo<'static>] = unsafe { std::mem::transmute(accounts) };
+
+    return mpl_token_metadata::processor::process_instruction(program_id, accounts, input);
+}
+
+pub fn config_address() -> Pubkey {
+    return Pubkey::find_program_address(&[CONFIG_PREFIX.as_bytes()], &::arising::ID).0;
+}
+
+pub fn forge_recipe_address(id: u32) -> Pubkey {
+    return Pubkey::find_program_address(
+        &[FORGE_RECIPE_PREFIX.as_bytes(), &id.to_le_bytes()],
+        &::arising::ID
+    ).0;
+}
+
+pub fn craft_recipe_address(id: u32) -> Pubkey {
+    return Pubkey::find_program_address(
+        &[CRAFT_RECIPE_PREFIX.as_bytes(), &id.to_le_bytes()],
+        &::arising::ID
+    ).0;
+}
+
+pub fn item_address(item_mint: &Pubkey) -> Pubkey {
+    return Pubkey::find_program_address(
+        &[ITEM_PREFIX.as_bytes(), &item_mint.to_bytes()],
+        &::arising::ID
+    ).0;
+}
+
+pub fn item_authority_address() -> Pubkey {
+    return Pubkey::find_program_address(&[ITEM_AUTHORITY_PREFIX.as_bytes()], &::arising::ID).0;
+}
+
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    return Pubkey::find_program_address(
+        &[METADATA_PREFIX.as_bytes(), &mpl_token_metadata::ID.to_bytes(), &mint.to_bytes()],
+        &mpl_token_metadata::ID
+    ).0;
+}
+
+pub fn item_metadata_address(item_mint: &Pubkey) -> Pubkey {
+    return metadata_address(item_mint);
+}
+
+/// The mint of the Arising characters collection NFT.
+pub fn collection_mint() -> Keypair {
+    return keypair_from_seed(&[42u8; 32]).unwrap();
+}
+
+/// The payer associated token account holding the item.
+pub fn item_token_address(context: &ProgramTestContext, item_mint: &Pubkey) -> Pubkey {
+    return get_associated_token_address(&context.payer.pubkey(), item_mint);
+}
+
+pub fn item_definition_address(id: u32) -> Pubkey {
+    return Pubkey::find_program_address(
+        &[ITEM_DEFINITION_PREFIX.as_bytes(), &id.to_le_bytes()],
+        &::arising::ID
+    ).0;
+}
+
+pub fn quest_address(id: u32) -> Pubkey {
+    return Pubkey::find_program_address(
+        &[QUESTS_PREFIX.as_bytes(), &id.to_le_bytes()],
+        &::arising::ID
+    ).0;
+}
+
+pub fn character_address(prefix: &str, mint: &Pubkey) -> Pubkey {
+    return Pubkey::find_program_address(&[prefix.as_bytes(), &mint.to_bytes()], &::arising::ID).0;
+}
+
+pub async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair]
+) -> std::result::Result<(), BanksClientError> {
+    let blockhash = context.banks_client
+        .get_new_latest_blockhash(&context.last_blockhash).await
+        .unwrap();
+    context.last_blockhash = blockhash;
+
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash
+    );
+
+    return context.banks_client.process_transaction(transaction).await;
+}
+
+pub fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    return Instruction {
+        program_id: ::arising::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    };
+}
+
+/// Returns the custom program error code of a failed transaction.
+pub fn error_code(result: std::result::Result<(), BanksClientError>) -> u32 {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => code,
+        error => panic!("unexpected error {:?}", error),
+    }
+}
+
+pub async fn fetch<T: AccountDeserialize>(context: &mut ProgramTestContext, address: Pubkey) -> T {
+    let account = context.banks_client.get_account(address).await.unwrap().unwrap();
+
+    return T::try_deserialize(&mut account.data.as_slice()).unwrap();
+}
+
+/// Moves the clock forward the amount of seconds.
+pub async fn warp_seconds(context: &mut ProgramTestContext, seconds: i64) {
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp += seconds;
+    context.set_sysvar(&clock);
+}
+
+/// Moves the bank a few slots forward so newer slot hashes are available.
+pub async fn warp_slots(context: &mut ProgramTestContext, slots: u64) {
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    context.warp_to_slot(clock.slot + slots).unwrap();
+}
+
+/// Moves the bank past the SlotHashes window, so only slot hashes newer than every pending
+/// commitment are available.
+pub async fn expire_slot_hashes(context: &mut ProgramTestContext) {
+    warp_slots(context, 600).await;
+
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+
+    // The warp only records the slot it lands on, fill the window with the slots before it
+    let mut entries = vec![];
+    let mut i = 0;
+    while i < 512 {
+        entries.push((clock.slot - 1 - i, Hash::new_unique()));
+        i += 1;
+    }
+
+    context.set_sysvar(&SlotHashes::new(&entries));
+}
+
+pub async fn fetch_token_account(
+    context: &mut ProgramTestContext,
+    address: Pubkey
+) -> spl_token::state::Account {
+    let account = context.banks_client.get_account(address).await.unwrap().unwrap();
+    return spl_token::state::Account::unpack(&account.data).unwrap();
+}
+
+pub async fn fetch_mint(context: &mut ProgramTestContext, address: Pubkey) -> spl_token::state::Mint {
+    let account = context.banks_client.get_account(address).await.unwrap().unwrap();
+    return spl_token::state::Mint::unpack(&account.data).unwrap();
+}
+
+/// Mints a new token with the amount to a token account owned by the payer.
+pub async fn mint_tokens(context: &mut ProgramTestContext, amount: u64) -> (Pubkey, Pubkey) {
+    return mint_tokens_with(context, &Keypair::new(), amount).await;
+}
+
+/// Mints the amount of a new token with the given mint keypair.
+pub async fn mint_tokens_with(
+    context: &mut ProgramTestContext,
+    mint: &Keypair,
+    amount: u64
+) -> (Pubkey, Pubkey) {
+    let token_account = Keypair::new();
+    let payer = context.payer.pubkey();
+
+    let rent = context.banks_client.get_rent().await.unwrap();
+
+    let instructions = [
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::ID
+        ),
+        spl_token::instruction
+            ::initialize_mint(&spl_token::ID, &mint.pubkey(), &payer, None, 0)
+            .unwrap(),
+        system_instruction::create_account(
+            &payer,
+            &token_account.pubkey(),
+            rent.minimum_balance(spl_token::state::Account::LEN),
+            spl_token::state::Account::LEN as u64,
+            &spl_token::ID
+        ),
+        spl_token::instruction
+            ::initialize_account(&spl_token::ID, &token_account.pubkey(), &mint.pubkey(), &payer)
+            .unwrap(),
+        spl_token::instruction
+            ::mint_to(&spl_token::ID, &mint.pubkey(), &token_account.pubkey(), &payer, &[], amount)
+            .unwrap(),
+    ];
+
+    process(context, &instructions, &[mint, &token_account]).await.unwrap();
+
+    return (mint.pubkey(), token_account.pubkey());
+}
+
+pub fn set_paused_instruction(context: &ProgramTestContext, paused: bool) -> Instruction {
+    let accounts = ::arising::accounts::SetPause {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+    };
+
+    return instruction(accounts, ::arising::instruction::SetPaused { paused });
+}
+
+/// Initializes and unpauses the program config with the payer as authority.
+pub async fn initialize(context: &mut ProgramTestContext) {
+    let accounts = ::arising::accounts::Initialize {
+        config: config_address(),
+        authority: context.payer.pubkey(),
+        system_program: system_program::ID,
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::Initialize { _bump: 0 });
+    let unpause = set_paused_instruction(context, false);
+
+    process(context, &[ix, unpause], &[]).await.unwrap();
+
+    add_character_collection(context).await;
+}
+
+/// Writes a token metadata created and verified by the payer.
+///
+/// The native token metadata program can't create accounts in program-test,
+/// so the metadata is set on the bank directly.
+pub async fn set_metadata(context: &mut ProgramTestContext, mint: &Pubkey, collection: Option<Collection>) {
+    let payer = context.payer.pubkey();
+
+    let metadata = Metadata {
+        key: Key::MetadataV1,
+        update_authority: payer,
+        mint: *mint,
+        data: Data {
+            name: "Arising".to_string(),
+            symbol: "ARISING".to_string(),
+            uri: "".to_string(),
+            seller_fee_basis_points: 0,
+            creators: Some(vec![Creator { address: payer, verified: true, share: 100 }]),
+        },
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: None,
+        collection,
+        uses: None,
+        collection_details: None,
+    };
+
+    let mut data = metadata.try_to_vec().unwrap();
+    data.resize(MAX_METADATA_LEN, 0);
+
+    let rent = context.banks_client.get_rent().await.unwrap();
+
+    let mut account = AccountSharedData::new(
+        rent.minimum_balance(MAX_METADATA_LEN),
+        MAX_METADATA_LEN,
+        &mpl_token_metadata::ID
+    );
+    account.set_data(data);
+
+    context.set_account(&metadata_address(mint), &account);
+}
+
+/// Mints the characters collection NFT and registers it with the payer as creator.
+pub async fn add_character_collection(context: &mut ProgramTestContext) {
+    let collection = collection_mint();
+
+    mint_tokens_with(context, &collection, 1).await;
+
+    let accounts = ::arising::accounts::SetCharacterCollection {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        collection_mint: collection.pubkey(),
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::SetCharacterCollection {
+        creator: context.payer.pubkey(),
+    });
+
+    process(context, &[ix], &[]).await.unwrap();
+}
+
+/// Mints a character NFT in the collection, verified in it if asked.
+pub async fn mint_collection_character(
+    context: &mut ProgramTestContext,
+    verified: bool
+) -> CharacterAccounts {
+    let (mint, token_account) = mint_tokens(context, 1).await;
+
+    let collection = Collection { verified, key: collection_mint().pubkey() };
+    set_metadata(context, &mint, Some(collection)).await;
+
+    return character_accounts(mint, token_account);
+}
+
+/// The program accounts of the character minted as the mint.
+pub fn character_accounts(mint: Pubkey, token_account: Pubkey) -> CharacterAccounts {
+    return CharacterAccounts {
+        mint,
+        token_account,
+        character: character_address(CHARACTER_PREFIX, &mint),
+        character_materials: character_address(CHARACTER_MATERIAL_PREFIX, &mint),
+        character_slots: character_address(CHARACTER_SLOTS_PREFIX, &mint),
+        character_equipment: character_address(CHARACTER_EQUIPMENT_PREFIX, &mint),
+    };
+}
+
+/// Mints a verified character NFT and returns its program accounts.
+pub async fn mint_character(context: &mut ProgramTestContext) -> CharacterAccounts {
+    return mint_collection_character(context, true).await;
+}
+
+pub fn add_character_instruction(context: &ProgramTestContext, character: &CharacterAccounts) -> Instruction {
+    let accounts = ::arising::accounts::AddCharacter {
+        payer: context.payer.pubkey(),
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        character_mint: character.mint,
+        character_metadata: metadata_address(&character.mint),
+        character: character.character,
+        character_materials: character.character_materials,
+        character_slots: character.character_slots,
+        character_equipment: character.character_equipment,
+        system_program: system_program::ID,
+    };
+
+    return instruction(accounts, ::arising::instruction::AddCharacter { mint: character.mint, _bump: 0 });
+}
+
+/// Mints a character NFT and registers it on the program.
+pub async fn add_character(context: &mut ProgramTestContext) -> CharacterAccounts {
+    let character = mint_character(context).await;
+
+    let ix = add_character_instruction(context, &character);
+    process(context, &[ix], &[]).await.unwrap();
+
+    return character;
+}
+
+pub fn character_access(
+    context: &ProgramTestContext,
+    character: &CharacterAccounts
+) -> ::arising::accounts::CharacterAccess {
+    return ::arising::accounts::CharacterAccess {
+        payer: context.payer.pubkey(),
+        character: character.character,
+        character_materials: character.character_materials,
+        character_slots: character.character_slots,
+        character_equipment: character.character_equipment,
+        config: config_address(),
+        character_token_account: character.token_account,
+        system_program: system_program::ID,
+    };
+}
+
+pub fn character_refresh(
+    context: &ProgramTestContext,
+    character: &CharacterAccounts
+) -> ::arising::accounts::CharacterRefresh {
+    return ::arising::accounts::CharacterRefresh {
+        payer: context.payer.pubkey(),
+        character: character.character,
+        config: config_address(),
+        character_token_account: character.token_account,
+    };
+}
+
+pub async fn assign_stats(
+    context: &mut ProgramTestContext,
+    character: &CharacterAccounts,
+    points: BaseStats
+) -> std::result::Result<(), BanksClientError> {
+    let ix = instruction(
+        character_access(context, character),
+        ::arising::instruction::AssignStatsCharacter { points }
+    );
+
+    return process(context, &[ix], &[]).await;
+}
+
+pub fn quest_access(
+    context: &ProgramTestContext,
+    character: &CharacterAccounts,
+    quest_id: u32
+) -> ::arising::accounts::QuestAccess {
+    return ::arising::accounts::QuestAccess {
+        payer: context.payer.pubkey(),
+        character: character.character,
+        character_materials: character.character_materials,
+        character_slots: character.character_slots,
+        character_equipment: character.character_equipment,
+        character_token_account: character.token_account,
+        config: config_address(),
+        quest: quest_address(quest_id),
+        slot_hashes: sysvar::slot_hashes::ID,
+    };
+}
+
+pub fn store_slot_hashes_instruction(character: &CharacterAccounts) -> Instruction {
+    let accounts = ::arising::accounts::StoreSlotHashes {
+        character: character.character,
+        character_slots: character.character_slots,
+        slot_hashes: sysvar::slot_hashes::ID,
+    };
+
+    return instruction(accounts, ::arising::instruction::StoreSlotHashes {});
+}
+
+pub fn job_quest(id: u32, materials_reward: [u32; 10], materials_amounts: [u32; 10]) -> Quest {
+    return Quest {
+        id,
+        name: "Beg".to_string(),
+        description: "Beg for materials".to_string(),
+        quest_type: QuestType::Job as u16,
+        stats_required: BaseStats { might: 1, speed: 0, intellect: 0 },
+        cooldown: 2,
+        level_required: 0,
+        materials_reward,
+        materials_amounts,
+        mob_experience: 0,
+        mob_level: 0,
+        mob_base_stats: BaseStats::default(),
+        mob_base_attributes: BaseAttributes::default(),
+        available: false,
+    };
+}
+
+pub fn add_quest_instruction(context: &ProgramTestContext, quest: Quest) -> Instruction {
+    let id = quest.id;
+
+    let accounts = ::arising::accounts::AddQuest {
+        payer: context.payer.pubkey(),
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        quest: quest_address(id),
+        system_program: system_program::ID,
+    };
+
+    return instruction(accounts, ::arising::instruction::AddQuest {
+        _bump: 0,
+        id,
+        data: quest,
+    });
+}
+
+/// Adds and enables a job quest rewarding raw materials.
+pub async fn add_job_quest(
+    context: &mut ProgramTestContext,
+    id: u32,
+    materials_reward: [u32; 10],
+    materials_amounts: [u32; 10]
+) {
+    let add = add_quest_instruction(context, job_quest(id, materials_reward, materials_amounts));
+
+    let accounts = ::arising::accounts::UpdateQuest {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        quest: quest_address(id),
+    };
+
+    let enable = instruction(accounts, ::arising::instruction::UpdateQuestAvailability {
+        available: true,
+    });
+
+    process(context, &[add, enable], &[]).await.unwrap();
+}
+
+/// Starts and claims a job quest, waiting for its cooldown.
+pub async fn complete_job_quest(
+    context: &mut ProgramTestContext,
+    character: &CharacterAccounts,
+    id: u32
+) {
+    let start = instruction(quest_access(context, character, id), ::arising::instruction::StartQuest {
+        commitment: [0; 32],
+    });
+    process(context, &[start], &[]).await.unwrap();
+
+    warp_seconds(context, 10).await;
+
+    let claim = instruction(quest_access(context, character, id), ::arising::instruction::ClaimQuest {
+        reveal: [0; 32],
+    });
+    process(context, &[claim], &[]).await.unwrap();
+}
+
+pub fn fixed(values: &[u32]) -> [u32; 10] {
+    let mut array = [0; 10];
+    array[..values.len()].copy_from_slice(values);
+    return array;
+}
+
+pub fn wood_plank_recipe() -> Recipe {
+    return Recipe {
+        id: 1,
+        name: "Forge a Wood Plank".to_string(),
+        materials: fixed(&[WOOD, GOLD]),
+        materials_amounts: fixed(&[5, 20]),
+        materials_types: [
+            ResourceType::Raw as u16,
+            ResourceType::Raw as u16,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+        ],
+        stats_required: BaseStats { might: 0, speed: 1, intellect: 1 },
+        stats_sacrificed: BaseStats::default(),
+        cooldown: 2,
+        level_required: 0,
+        item_rewarded: WOOD_PLANK,
+        item_rewarded_amount: 1,
+        item_rewarded_type: ResourceType::Basic as u16,
+        available: false,
+    };
+}
+
+pub fn add_forge_recipe_instruction(
+    context: &ProgramTestContext,
+    recipe: Recipe
+) -> Instruction {
+    let id = recipe.id;
+
+    let accounts = ::arising::accounts::AddForgeRecipe {
+        payer: context.payer.pubkey(),
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        forge_recipe: forge_recipe_address(id),
+        system_program: system_program::ID,
+    };
+
+    return instruction(accounts, ::arising::instruction::AddForgeRecipe {
+        _bump: 0,
+        id,
+        data: recipe,
+    });
+}
+
+pub async fn add_forge_recipe(context: &mut ProgramTestContext, recipe: Recipe) {
+    let id = recipe.id;
+    let add = add_forge_recipe_instruction(context, recipe);
+
+    let accounts = ::arising::accounts::UpdateForgeRecipe {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        forge_recipe: forge_recipe_address(id),
+    };
+
+    let enable = instruction(accounts, ::arising::instruction::UpdateForgeRecipeAvailability {
+        available: true,
+    });
+
+    process(context, &[add, enable], &[]).await.unwrap();
+}
+
+pub fn forge_access(
+    context: &ProgramTestContext,
+    character: &CharacterAccounts,
+    recipe_id: u32
+) -> ::arising::accounts::ForgeAccess {
+    return ::arising::accounts::ForgeAccess {
+        payer: context.payer.pubkey(),
+        character: character.character,
+        character_materials: character.character_materials,
+        character_slots: character.character_slots,
+        character_token_account: character.token_account,
+        config: config_address(),
+        forge_recipe: forge_recipe_address(recipe_id),
+    };
+}
+
+pub async fn start_forge(
+    context: &mut ProgramTestContext,
+    character: &CharacterAccounts,
+    recipe_id: u32
+) -> std::result::Result<(), BanksClientError> {
+    let ix = instruction(
+        forge_access(context, character, recipe_id),
+        ::arising::instruction::StartForge {}
+    );
+
+    return process(context, &[ix], &[]).await;
+}
+
+pub async fn claim_forge(
+    context: &mut ProgramTestContext,
+    character: &CharacterAccounts,
+    recipe_id: u32
+) -> std::result::Result<(), BanksClientError> {
+    let ix = instruction(
+        forge_access(context, character, recipe_id),
+        ::arising::instruction::ClaimForge {}
+    );
+
+    return process(context, &[ix], &[]).await;
+}
+
+pub fn bone_dagger_definition() -> ItemDefinition {
+    return ItemDefinition {
+        id: BONE_DAGGER,
+        name: "Bone Dagger".to_string(),
+        uri: "https://arising.game/items/1.json".to_string(),
+        item_type: ItemType::OneHanded as u16,
+        stats_additions: BaseStats::default(),
+        attributes_additions: BaseAttributes { atk: 1, range: 1, rate: 1, ..BaseAttributes::default() },
+        stats_reductions: BaseStats::default(),
+        attributes_reductions: BaseAttributes::default(),
+        level_required: 0,
+    };
+}
+
+pub fn add_item_definition_instruction(
+    context: &ProgramTestContext,
+    definition: ItemDefinition
+) -> Instruction {
+    let id = definition.id;
+
+    let accounts = ::arising::accounts::AddItemDefinition {
+        payer: context.payer.pubkey(),
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        item_definition: item_definition_address(id),
+        system_program: system_program::ID,
+    };
+
+    return instruction(accounts, ::arising::instruction::AddItemDefinition {
+        _bump: 0,
+        id,
+        data: definition,
+    });
+}
+
+pub async fn add_item_definition(context: &mut ProgramTestContext, definition: ItemDefinition) {
+    let ix = add_item_definition_instruction(context, definition);
+
+    process(context, &[ix], &[]).await.unwrap();
+}
+
+pub fn bone_dagger_recipe() -> Recipe {
+    return Recipe {
+        id: 1,
+        name: "Craft a Bone Dagger".to_string(),
+        materials: fixed(&[WOOD_PLANK]),
+        materials_amounts: fixed(&[1]),
+        materials_types: [ResourceType::Basic as u16, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        stats_required: BaseStats { might: 1, speed: 0, intellect: 0 },
+        stats_sacrificed: BaseStats::default(),
+        cooldown: 2,
+        level_required: 0,
+        item_rewarded: BONE_DAGGER,
+        item_rewarded_amount: 1,
+        item_rewarded_type: ResourceType::Item as u16,
+        available: false,
+    };
+}
+
+pub fn add_craft_recipe_instruction(
+    context: &ProgramTestContext,
+    recipe: Recipe
+) -> Instruction {
+    let id = recipe.id;
+
+    let accounts = ::arising::accounts::AddCraftRecipe {
+        payer: context.payer.pubkey(),
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        craft_recipe: craft_recipe_address(id),
+        system_program: system_program::ID,
+    };
+
+    return instruction(accounts, ::arising::instruction::AddCraftRecipe {
+        _bump: 0,
+        id,
+        data: recipe,
+    });
+}
+
+pub async fn add_craft_recipe(context: &mut ProgramTestContext, recipe: Recipe) {
+    let id = recipe.id;
+    let add = add_craft_recipe_instruction(context, recipe);
+
+    let accounts = ::arising::accounts::UpdateCraftRecipe {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        craft_recipe: craft_recipe_address(id),
+    };
+
+    let enable = instruction(accounts, ::arising::instruction::UpdateCraftRecipeAvailability {
+        available: true,
+    });
+
+    process(context, &[add, enable], &[]).await.unwrap();
+}
+
+pub async fn start_craft(
+    context: &mut ProgramTestContext,
+    character: &CharacterAccounts,
+    recipe_id: u32
+) -> std::result::Result<(), BanksClientError> {
+    let accounts = ::arising::accounts::CraftAccess {
+        payer: context.payer.pubkey(),
+        character: character.character,
+        character_materials: character.character_materials,
+        character_slots: character.character_slots,
+        character_token_account: character.token_account,
+        config: config_address(),
+        craft_recipe: craft_recipe_address(recipe_id),
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::StartCraft {});
+
+    return process(context, &[ix], &[]).await;
+}
+
+pub async fn claim_craft(
+    context: &mut ProgramTestContext,
+    character: &CharacterAccounts,
+    recipe_id: u32,
+    item_mint: &Keypair
+) -> std::result::Result<(), BanksClientError> {
+    let recipe: CraftRecipe = fetch(context, craft_recipe_address(recipe_id)).await;
+    let mint = item_mint.pubkey();
+
+    let accounts = ::arising::accounts::ClaimCraft {
+        payer: context.payer.pubkey(),
+        character: character.character,
+        character_slots: character.character_slots,
+        character_token_account: character.token_account,
+        config: config_address(),
+        craft_recipe: craft_recipe_address(recipe_id),
+        item_definition: item_definition_address(recipe.recipe.item_rewarded),
+        item_mint: mint,
+        item_token_account: item_token_address(context, &mint),
+        item: item_address(&mint),
+        item_authority: item_authority_address(),
+        item_metadata: item_metadata_address(&mint),
+        token_metadata_program: mpl_token_metadata::ID,
+        token_program: spl_token::ID,
+        associated_token_program: spl_associated_token_account::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::ClaimCraft {});
+
+    return process(context, &[ix], &[item_mint]).await;
+}
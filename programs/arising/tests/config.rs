@@ -0,0 +1,230 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use ::arising::*;
+use common::*;
+use solana_program_test::*;
+use solana_sdk::signature::{ Keypair, Signer };
+
+async fn set_subsystem_paused(
+    context: &mut ProgramTestContext,
+    subsystem: u16,
+    paused: bool
+) -> std::result::Result<(), BanksClientError> {
+    let accounts = ::arising::accounts::SetPause {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::SetSubsystemPaused {
+        subsystem,
+        paused,
+    });
+
+    return process(context, &[ix], &[]).await;
+}
+
+async fn update_config(
+    context: &mut ProgramTestContext,
+    seconds_between_refreshes: Option<u64>,
+    seconds_between_paid_refreshes: Option<u64>,
+    max_characters: Option<u64>
+) -> std::result::Result<(), BanksClientError> {
+    let accounts = ::arising::accounts::UpdateConfig {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::UpdateConfig {
+        seconds_between_refreshes,
+        seconds_between_paid_refreshes,
+        max_characters,
+    });
+
+    return process(context, &[ix], &[]).await;
+}
+
+#[tokio::test]
+async fn update_config_only_changes_the_given_fields() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    update_config(&mut context, Some(3_600), None, None).await.unwrap();
+
+    let config: Config = fetch(&mut context, config_address()).await;
+    assert_eq!(config.seconds_between_refreshes, 3_600);
+    assert_eq!(config.seconds_between_paid_refreshes, 86_400);
+    assert_eq!(config.max_characters, 30_000);
+
+    update_config(&mut context, None, Some(7_200), Some(100)).await.unwrap();
+
+    let config: Config = fetch(&mut context, config_address()).await;
+    assert_eq!(config.seconds_between_refreshes, 3_600);
+    assert_eq!(config.seconds_between_paid_refreshes, 7_200);
+    assert_eq!(config.max_characters, 100);
+}
+
+#[tokio::test]
+async fn update_config_is_validated() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    add_character(&mut context).await;
+    add_character(&mut context).await;
+
+    let config: Config = fetch(&mut context, config_address()).await;
+    assert_eq!(config.characters, 2);
+
+    let result = update_config(&mut context, Some(0), None, None).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidRefreshInterval));
+
+    let result = update_config(&mut context, None, Some(0), None).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidRefreshInterval));
+
+    let result = update_config(&mut context, Some(u64::MAX), None, None).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidRefreshInterval));
+
+    let result = update_config(&mut context, None, Some(MAX_SECONDS_BETWEEN_REFRESHES + 1), None).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidRefreshInterval));
+
+    let result = update_config(&mut context, None, None, Some(1)).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidMaxCharacters));
+
+    update_config(&mut context, None, None, Some(2)).await.unwrap();
+}
+
+#[tokio::test]
+async fn characters_are_capped() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    add_character(&mut context).await;
+    update_config(&mut context, None, None, Some(2)).await.unwrap();
+
+    add_character(&mut context).await;
+
+    let character = mint_character(&mut context).await;
+    let ix = add_character_instruction(&context, &character);
+    let result = process(&mut context, &[ix], &[]).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::CharacterCapReached));
+
+    let config: Config = fetch(&mut context, config_address()).await;
+    assert_eq!(config.characters, 2);
+}
+
+#[tokio::test]
+async fn pause_blocks_player_instructions() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let character = add_character(&mut context).await;
+
+    let ix = set_paused_instruction(&context, true);
+    process(&mut context, &[ix], &[]).await.unwrap();
+
+    let result = assign_stats(&mut context, &character, BaseStats { might: 1, speed: 0, intellect: 0 }).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::Paused));
+
+    let ix = set_paused_instruction(&context, false);
+    process(&mut context, &[ix], &[]).await.unwrap();
+
+    assign_stats(&mut context, &character, BaseStats { might: 1, speed: 0, intellect: 0 }).await.unwrap();
+}
+
+#[tokio::test]
+async fn subsystems_pause_on_their_own() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let character = add_character(&mut context).await;
+    add_forge_recipe(&mut context, wood_plank_recipe()).await;
+    add_job_quest(&mut context, 1, fixed(&[WOOD]), fixed(&[10])).await;
+
+    set_subsystem_paused(&mut context, Subsystem::Forge as u16, true).await.unwrap();
+    set_subsystem_paused(&mut context, Subsystem::Quests as u16, true).await.unwrap();
+    set_subsystem_paused(&mut context, Subsystem::Refresh as u16, true).await.unwrap();
+
+    let result = start_forge(&mut context, &character, 1).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::Paused));
+
+    let ix = instruction(quest_access(&context, &character, 1), ::arising::instruction::StartQuest {
+        commitment: [0; 32],
+    });
+    let result = process(&mut context, &[ix], &[]).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::Paused));
+
+    let ix = instruction(character_refresh(&context, &character), ::arising::instruction::PerformRefresh {});
+    let result = process(&mut context, &[ix], &[]).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::Paused));
+
+    // The rest of the game keeps running
+    assign_stats(&mut context, &character, BaseStats { might: 1, speed: 0, intellect: 0 }).await.unwrap();
+
+    set_subsystem_paused(&mut context, Subsystem::Quests as u16, false).await.unwrap();
+
+    let ix = instruction(quest_access(&context, &character, 1), ::arising::instruction::StartQuest {
+        commitment: [0; 32],
+    });
+    process(&mut context, &[ix], &[]).await.unwrap();
+
+    let result = set_subsystem_paused(&mut context, Subsystem::Game as u16, true).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidSubsystem));
+}
+
+#[tokio::test]
+async fn authority_is_transferred_in_two_steps() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let new_authority = Keypair::new();
+
+    let accounts = ::arising::accounts::ProposeAuthority {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+    };
+    let ix = instruction(accounts, ::arising::instruction::ProposeAuthority {
+        new_authority: new_authority.pubkey(),
+    });
+    process(&mut context, &[ix], &[]).await.unwrap();
+
+    // Only the proposed authority can accept
+    let accounts = ::arising::accounts::AcceptAuthority {
+        pending_authority: context.payer.pubkey(),
+        config: config_address(),
+    };
+    let ix = instruction(accounts, ::arising::instruction::AcceptAuthority {});
+    let result = process(&mut context, &[ix], &[]).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidPendingAuthority));
+
+    let accounts = ::arising::accounts::AcceptAuthority {
+        pending_authority: new_authority.pubkey(),
+        config: config_address(),
+    };
+    let ix = instruction(accounts, ::arising::instruction::AcceptAuthority {});
+    process(&mut context, &[ix], &[&new_authority]).await.unwrap();
+
+    let config: Config = fetch(&mut context, config_address()).await;
+    assert_eq!(config.authority, new_authority.pubkey());
+    assert_eq!(config.pending_authority, Pubkey::default());
+
+    let ix = set_paused_instruction(&context, true);
+    let result = process(&mut context, &[ix], &[]).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidAuthority));
+
+    let accounts = ::arising::accounts::SetPause {
+        authority: new_authority.pubkey(),
+        config: config_address(),
+        operator: None,
+    };
+    let ix = instruction(accounts, ::arising::instruction::SetPaused { paused: true });
+    process(&mut context, &[ix], &[&new_authority]).await.unwrap();
+}
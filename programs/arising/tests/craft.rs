@@ -0,0 +1,138 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use ::arising::*;
+use common::*;
+use solana_program_test::*;
+use solana_sdk::{ program_option::COption, signature::{ Keypair, Signer } };
+
+/// Initializes the program with a character holding a forged wood plank and the craft recipe.
+async fn setup() -> (ProgramTestContext, CharacterAccounts) {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let character = add_character(&mut context).await;
+    assign_stats(&mut context, &character, BaseStats { might: 2, speed: 2, intellect: 2 }).await
+        .unwrap();
+
+    add_job_quest(&mut context, 1, fixed(&[WOOD, GOLD]), fixed(&[10, 30])).await;
+    complete_job_quest(&mut context, &character, 1).await;
+
+    add_forge_recipe(&mut context, wood_plank_recipe()).await;
+    start_forge(&mut context, &character, 1).await.unwrap();
+    warp_seconds(&mut context, 10).await;
+    claim_forge(&mut context, &character, 1).await.unwrap();
+
+    add_item_definition(&mut context, bone_dagger_definition()).await;
+    add_craft_recipe(&mut context, bone_dagger_recipe()).await;
+
+    return (context, character);
+}
+
+#[tokio::test]
+async fn craft_mints_an_item_token() {
+    let (mut context, character) = setup().await;
+
+    start_craft(&mut context, &character, 1).await.unwrap();
+
+    let materials: CharacterMaterials = fetch(&mut context, character.character_materials).await;
+    assert_eq!(materials.basic[(WOOD_PLANK - 1) as usize], 0);
+
+    let dagger = Keypair::new();
+
+    // The recipe cooldown has not passed yet
+    let result = claim_craft(&mut context, &character, 1, &dagger).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::NotAbleToClaimCraftRecipe));
+
+    warp_seconds(&mut context, 10).await;
+
+    claim_craft(&mut context, &character, 1, &dagger).await.unwrap();
+
+    let item: ItemInstance = fetch(&mut context, item_address(&dagger.pubkey())).await;
+    assert_eq!(item.id, BONE_DAGGER);
+    assert_eq!(item.mint, dagger.pubkey());
+    assert_eq!(item.tier, 0);
+    assert_eq!(item.durability, MAX_DURABILITY);
+
+    // A single token is held by the player and no more can be minted
+    let mint = fetch_mint(&mut context, dagger.pubkey()).await;
+    assert_eq!(mint.supply, 1);
+    assert_eq!(mint.decimals, 0);
+    assert_eq!(mint.mint_authority, COption::None);
+    assert_eq!(mint.freeze_authority, COption::Some(item_authority_address()));
+
+    let token_address = item_token_address(&context, &dagger.pubkey());
+    let token_account = fetch_token_account(&mut context, token_address).await;
+    assert_eq!(token_account.amount, 1);
+    assert_eq!(token_account.owner, context.payer.pubkey());
+
+    // The native test runtime doesn't carry the data of accounts created in nested
+    // invocations back, so only the metadata account creation is checked here.
+    let metadata = context.banks_client
+        .get_account(item_metadata_address(&dagger.pubkey())).await
+        .unwrap()
+        .unwrap();
+    assert_eq!(metadata.owner, mpl_token_metadata::ID);
+
+    let slots: CharacterSlots = fetch(&mut context, character.character_slots).await;
+    assert!(slots.craft.last_task_claimed);
+    assert_eq!(slots.craft.last_task_id, 1);
+
+    let config: Config = fetch(&mut context, config_address()).await;
+    assert_eq!(config.craft_recipes, 1);
+}
+
+#[tokio::test]
+async fn craft_can_only_be_claimed_once() {
+    let (mut context, character) = setup().await;
+
+    start_craft(&mut context, &character, 1).await.unwrap();
+    warp_seconds(&mut context, 10).await;
+    claim_craft(&mut context, &character, 1, &Keypair::new()).await.unwrap();
+
+    let result = claim_craft(&mut context, &character, 1, &Keypair::new()).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::NotAbleToClaimCraftRecipe));
+}
+
+#[tokio::test]
+async fn craft_requires_materials() {
+    let (mut context, character) = setup().await;
+
+    let mut recipe = bone_dagger_recipe();
+    recipe.id = 2;
+    recipe.materials_amounts = fixed(&[2]);
+    add_craft_recipe(&mut context, recipe).await;
+
+    let result = start_craft(&mut context, &character, 2).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::NotEnoughResources));
+}
+
+#[tokio::test]
+async fn craft_recipes_must_reward_an_item() {
+    let (mut context, _) = setup().await;
+
+    let mut recipe = bone_dagger_recipe();
+    recipe.id = 2;
+    recipe.item_rewarded_type = ResourceType::Basic as u16;
+
+    let ix = add_craft_recipe_instruction(&context, recipe);
+    let result = process(&mut context, &[ix], &[]).await;
+
+    assert_eq!(error_code(result), u32::from(CraftError::InvalidReward));
+}
+
+#[tokio::test]
+async fn craft_recipes_must_reward_a_defined_item() {
+    let (mut context, _) = setup().await;
+
+    let mut recipe = bone_dagger_recipe();
+    recipe.id = 2;
+    recipe.item_rewarded = BONE_DAGGER + 1;
+
+    let ix = add_craft_recipe_instruction(&context, recipe);
+    let result = process(&mut context, &[ix], &[]).await;
+
+    assert_eq!(error_code(result), u32::from(ItemError::InvalidID));
+}
@@ -0,0 +1,371 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use anchor_lang::{ prelude::{ AccountMeta, Pubkey }, AnchorDeserialize, AnchorSerialize };
+use ::arising::*;
+use common::*;
+use solana_program_test::*;
+use anchor_spl::token::spl_token::{ self, state::AccountState };
+use solana_sdk::{
+    program_pack::Pack,
+    signature::{ Keypair, Signer },
+    system_instruction,
+    transaction::Transaction,
+};
+
+const BONE_GREATSWORD: u32 = 2;
+
+fn bone_greatsword_definition() -> ItemDefinition {
+    return ItemDefinition {
+        id: BONE_GREATSWORD,
+        name: "Bone Greatsword".to_string(),
+        uri: "https://arising.game/items/2.json".to_string(),
+        item_type: ItemType::TwoHanded as u16,
+        stats_additions: BaseStats { might: 1, speed: 0, intellect: 0 },
+        attributes_additions: BaseAttributes { atk: 3, ..BaseAttributes::default() },
+        stats_reductions: BaseStats { might: 0, speed: 1, intellect: 0 },
+        attributes_reductions: BaseAttributes::default(),
+        level_required: 0,
+    };
+}
+
+fn bone_greatsword_recipe() -> Recipe {
+    let mut recipe = bone_dagger_recipe();
+
+    recipe.id = 2;
+    recipe.name = "Craft a Bone Greatsword".to_string();
+    recipe.materials = fixed(&[WOOD]);
+    recipe.materials_amounts = fixed(&[5]);
+    recipe.materials_types = [ResourceType::Raw as u16, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    recipe.stats_required = BaseStats::default();
+    recipe.item_rewarded = BONE_GREATSWORD;
+
+    return recipe;
+}
+
+async fn equip_item(
+    context: &mut ProgramTestContext,
+    character: &CharacterAccounts,
+    item_mint: &Pubkey,
+    item_id: u32,
+    slot: EquipmentSlot
+) -> std::result::Result<(), BanksClientError> {
+    let accounts = equipment_access(context, character, item_mint, item_id);
+    let ix = instruction(accounts, ::arising::instruction::EquipItem { slot: slot as u16 });
+
+    return process(context, &[ix], &[]).await;
+}
+
+async fn unequip_item(
+    context: &mut ProgramTestContext,
+    character: &CharacterAccounts,
+    item_mint: &Pubkey,
+    slot: EquipmentSlot
+) -> std::result::Result<(), BanksClientError> {
+    let accounts = ::arising::accounts::UnequipAccess {
+        payer: context.payer.pubkey(),
+        character: character.character,
+        character_equipment: character.character_equipment,
+        config: config_address(),
+        item: item_address(item_mint),
+        item_mint: *item_mint,
+        item_token_account: item_token_address(context, item_mint),
+        item_authority: item_authority_address(),
+        token_program: spl_token::ID,
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::UnequipItem { slot: slot as u16 });
+
+    return process(context, &[ix], &[]).await;
+}
+
+fn equipment_access(
+    context: &ProgramTestContext,
+    character: &CharacterAccounts,
+    item_mint: &Pubkey,
+    item_id: u32
+) -> ::arising::accounts::EquipmentAccess {
+    return ::arising::accounts::EquipmentAccess {
+        payer: context.payer.pubkey(),
+        character: character.character,
+        character_equipment: character.character_equipment,
+        character_token_account: character.token_account,
+        config: config_address(),
+        item: item_address(item_mint),
+        item_definition: item_definition_address(item_id),
+        item_mint: *item_mint,
+        item_token_account: item_token_address(context, item_mint),
+        item_authority: item_authority_address(),
+        token_program: spl_token::ID,
+    };
+}
+
+/// Simulates the effective stats view with the given equipped items accounts.
+async fn effective_stats_view(
+    context: &mut ProgramTestContext,
+    character: &CharacterAccounts,
+    equipped: &[Pubkey]
+) -> std::result::Result<EffectiveStats, u32> {
+    let accounts = ::arising::accounts::CharacterStatsView {
+        character: character.character,
+        character_equipment: character.character_equipment,
+    };
+
+    let mut ix = instruction(accounts, ::arising::instruction::CharacterEffectiveStats {});
+
+    for key in equipped {
+        ix.accounts.push(AccountMeta::new_readonly(*key, false));
+    }
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash
+    );
+
+    let simulation = context.banks_client.simulate_transaction(transaction).await.unwrap();
+
+    if let Err(error) = simulation.result.unwrap() {
+        return Err(error_code(Err(BanksClientError::TransactionError(error))));
+    }
+
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    assert_eq!(return_data.program_id, ::arising::ID);
+
+    // Trailing zero bytes are trimmed from the return data
+    let mut data = return_data.data;
+    data.resize(EffectiveStats::default().try_to_vec().unwrap().len(), 0);
+
+    return Ok(EffectiveStats::deserialize(&mut &data[..]).unwrap());
+}
+
+/// Initializes the program with a character owning a bone dagger and a bone greatsword.
+async fn setup() -> (ProgramTestContext, CharacterAccounts, Pubkey, Pubkey) {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let character = add_character(&mut context).await;
+    assign_stats(&mut context, &character, BaseStats { might: 2, speed: 2, intellect: 2 }).await
+        .unwrap();
+
+    add_job_quest(&mut context, 1, fixed(&[WOOD, GOLD]), fixed(&[10, 30])).await;
+    complete_job_quest(&mut context, &character, 1).await;
+
+    add_forge_recipe(&mut context, wood_plank_recipe()).await;
+    start_forge(&mut context, &character, 1).await.unwrap();
+    warp_seconds(&mut context, 10).await;
+    claim_forge(&mut context, &character, 1).await.unwrap();
+
+    add_item_definition(&mut context, bone_dagger_definition()).await;
+    add_item_definition(&mut context, bone_greatsword_definition()).await;
+    add_craft_recipe(&mut context, bone_dagger_recipe()).await;
+    add_craft_recipe(&mut context, bone_greatsword_recipe()).await;
+
+    let dagger = Keypair::new();
+    start_craft(&mut context, &character, 1).await.unwrap();
+    warp_seconds(&mut context, 10).await;
+    claim_craft(&mut context, &character, 1, &dagger).await.unwrap();
+
+    let greatsword = Keypair::new();
+    start_craft(&mut context, &character, 2).await.unwrap();
+    warp_seconds(&mut context, 10).await;
+    claim_craft(&mut context, &character, 2, &greatsword).await.unwrap();
+
+    return (context, character, dagger.pubkey(), greatsword.pubkey());
+}
+
+#[tokio::test]
+async fn equip_and_unequip_an_item() {
+    let (mut context, character, dagger, _) = setup().await;
+
+    equip_item(&mut context, &character, &dagger, BONE_DAGGER, EquipmentSlot::RightHand).await.unwrap();
+
+    let equipment: CharacterEquipment = fetch(&mut context, character.character_equipment).await;
+    assert_eq!(equipment.right_hand, item_address(&dagger));
+    assert_eq!(equipment.left_hand, Pubkey::default());
+
+    let item: ItemInstance = fetch(&mut context, item_address(&dagger)).await;
+    assert!(item.equipped);
+
+    // The item token can't be moved while equipped
+    let token_address = item_token_address(&context, &dagger);
+    let token_account = fetch_token_account(&mut context, token_address).await;
+    assert_eq!(token_account.state, AccountState::Frozen);
+
+    // An equipped item is locked
+    let result = equip_item(&mut context, &character, &dagger, BONE_DAGGER, EquipmentSlot::LeftHand).await;
+    assert_eq!(error_code(result), u32::from(ItemError::ItemLocked));
+
+    let result = unequip_item(&mut context, &character, &dagger, EquipmentSlot::LeftHand).await;
+    assert_eq!(error_code(result), u32::from(ItemError::ItemNotEquipped));
+
+    unequip_item(&mut context, &character, &dagger, EquipmentSlot::RightHand).await.unwrap();
+
+    let equipment: CharacterEquipment = fetch(&mut context, character.character_equipment).await;
+    assert_eq!(equipment.right_hand, Pubkey::default());
+
+    let item: ItemInstance = fetch(&mut context, item_address(&dagger)).await;
+    assert!(!item.equipped);
+
+    let token_account = fetch_token_account(&mut context, token_address).await;
+    assert_eq!(token_account.state, AccountState::Initialized);
+}
+
+#[tokio::test]
+async fn items_can_be_unequipped_after_the_character_is_transferred() {
+    let (mut context, character, dagger, _) = setup().await;
+
+    equip_item(&mut context, &character, &dagger, BONE_DAGGER, EquipmentSlot::RightHand).await.unwrap();
+
+    // Move the character NFT to another wallet
+    let new_owner = Keypair::new();
+    let new_token_account = Keypair::new();
+    let payer = context.payer.pubkey();
+    let rent = context.banks_client.get_rent().await.unwrap();
+
+    let instructions = [
+        system_instruction::create_account(
+            &payer,
+            &new_token_account.pubkey(),
+            rent.minimum_balance(spl_token::state::Account::LEN),
+            spl_token::state::Account::LEN as u64,
+            &spl_token::ID
+        ),
+        spl_token::instruction
+            ::initialize_account(&spl_token::ID, &new_token_account.pubkey(), &character.mint, &new_owner.pubkey())
+            .unwrap(),
+        spl_token::instruction
+            ::transfer(&spl_token::ID, &character.token_account, &new_token_account.pubkey(), &payer, &[], 1)
+            .unwrap(),
+    ];
+    process(&mut context, &instructions, &[&new_token_account]).await.unwrap();
+
+    // The item owner no longer owns the character but can still take the item back
+    let result = equip_item(&mut context, &character, &dagger, BONE_DAGGER, EquipmentSlot::LeftHand).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidOwner));
+
+    unequip_item(&mut context, &character, &dagger, EquipmentSlot::RightHand).await.unwrap();
+
+    let equipment: CharacterEquipment = fetch(&mut context, character.character_equipment).await;
+    assert_eq!(equipment.right_hand, Pubkey::default());
+
+    let token_address = item_token_address(&context, &dagger);
+    let token_account = fetch_token_account(&mut context, token_address).await;
+    assert_eq!(token_account.state, AccountState::Initialized);
+}
+
+#[tokio::test]
+async fn equip_requires_a_matching_slot() {
+    let (mut context, character, dagger, _) = setup().await;
+
+    let result = equip_item(&mut context, &character, &dagger, BONE_DAGGER, EquipmentSlot::Helmet).await;
+    assert_eq!(error_code(result), u32::from(ItemError::InvalidSlot));
+
+    let accounts = equipment_access(&context, &character, &dagger, BONE_DAGGER);
+    let ix = instruction(accounts, ::arising::instruction::EquipItem { slot: 14 });
+    let result = process(&mut context, &[ix], &[]).await;
+    assert_eq!(error_code(result), u32::from(ItemError::InvalidSlot));
+}
+
+#[tokio::test]
+async fn equip_requires_the_item_level() {
+    let (mut context, character, dagger, _) = setup().await;
+
+    let mut data = bone_dagger_definition();
+    data.level_required = 3;
+
+    let accounts = ::arising::accounts::UpdateItemDefinition {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        item_definition: item_definition_address(BONE_DAGGER),
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::UpdateItemDefinition { data });
+    process(&mut context, &[ix], &[]).await.unwrap();
+
+    let result = equip_item(&mut context, &character, &dagger, BONE_DAGGER, EquipmentSlot::RightHand).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::NotEnoughLevel));
+}
+
+#[tokio::test]
+async fn two_handed_items_take_both_hands() {
+    let (mut context, character, dagger, greatsword) = setup().await;
+
+    equip_item(&mut context, &character, &dagger, BONE_DAGGER, EquipmentSlot::LeftHand).await.unwrap();
+
+    let result = equip_item(&mut context, &character, &greatsword, BONE_GREATSWORD, EquipmentSlot::RightHand).await;
+    assert_eq!(error_code(result), u32::from(ItemError::HandsOccupied));
+
+    unequip_item(&mut context, &character, &dagger, EquipmentSlot::LeftHand).await.unwrap();
+    equip_item(&mut context, &character, &greatsword, BONE_GREATSWORD, EquipmentSlot::RightHand).await.unwrap();
+
+    let equipment: CharacterEquipment = fetch(&mut context, character.character_equipment).await;
+    assert_eq!(equipment.right_hand, item_address(&greatsword));
+    assert_eq!(equipment.left_hand, item_address(&greatsword));
+
+    let result = equip_item(&mut context, &character, &dagger, BONE_DAGGER, EquipmentSlot::LeftHand).await;
+    assert_eq!(error_code(result), u32::from(ItemError::SlotOccupied));
+
+    unequip_item(&mut context, &character, &greatsword, EquipmentSlot::LeftHand).await.unwrap();
+
+    let equipment: CharacterEquipment = fetch(&mut context, character.character_equipment).await;
+    assert_eq!(equipment.right_hand, Pubkey::default());
+    assert_eq!(equipment.left_hand, Pubkey::default());
+}
+
+#[tokio::test]
+async fn unequip_frees_both_hands_after_the_item_type_changes() {
+    let (mut context, character, _, greatsword) = setup().await;
+
+    equip_item(&mut context, &character, &greatsword, BONE_GREATSWORD, EquipmentSlot::RightHand).await.unwrap();
+
+    let mut data = bone_greatsword_definition();
+    data.item_type = ItemType::OneHanded as u16;
+
+    let accounts = ::arising::accounts::UpdateItemDefinition {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        item_definition: item_definition_address(BONE_GREATSWORD),
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::UpdateItemDefinition { data });
+    process(&mut context, &[ix], &[]).await.unwrap();
+
+    unequip_item(&mut context, &character, &greatsword, EquipmentSlot::RightHand).await.unwrap();
+
+    let equipment: CharacterEquipment = fetch(&mut context, character.character_equipment).await;
+    assert_eq!(equipment.right_hand, Pubkey::default());
+    assert_eq!(equipment.left_hand, Pubkey::default());
+}
+
+#[tokio::test]
+async fn effective_stats_include_the_equipment() {
+    let (mut context, character, _, greatsword) = setup().await;
+
+    let stats = effective_stats_view(&mut context, &character, &[]).await.unwrap();
+    assert_eq!(stats.stats.might, 2);
+    assert_eq!(stats.stats.speed, 2);
+    assert_eq!(stats.attributes.atk, 0);
+
+    equip_item(&mut context, &character, &greatsword, BONE_GREATSWORD, EquipmentSlot::LeftHand).await.unwrap();
+
+    let accounts = [item_address(&greatsword), item_definition_address(BONE_GREATSWORD)];
+
+    let stats = effective_stats_view(&mut context, &character, &accounts).await.unwrap();
+    assert_eq!(stats.stats.might, 3);
+    assert_eq!(stats.stats.speed, 1);
+    assert_eq!(stats.attributes.atk, 3);
+
+    // Every equipped item must be given with its definition
+    let result = effective_stats_view(&mut context, &character, &[]).await;
+    assert_eq!(result.err(), Some(u32::from(ItemError::InvalidEquippedAccounts)));
+
+    let wrong_definition = [item_address(&greatsword), item_definition_address(BONE_DAGGER)];
+    let result = effective_stats_view(&mut context, &character, &wrong_definition).await;
+    assert_eq!(result.err(), Some(u32::from(ItemError::InvalidEquippedAccounts)));
+}
@@ -0,0 +1,172 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use ::arising::*;
+use common::*;
+use solana_program_test::*;
+use solana_sdk::signature::Signer;
+
+/// Initializes the program with a forge recipe and a character holding stats to spend.
+async fn setup() -> (ProgramTestContext, CharacterAccounts) {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let character = add_character(&mut context).await;
+    assign_stats(&mut context, &character, BaseStats { might: 2, speed: 2, intellect: 2 }).await
+        .unwrap();
+
+    add_forge_recipe(&mut context, wood_plank_recipe()).await;
+
+    return (context, character);
+}
+
+#[tokio::test]
+async fn forge_consumes_materials_and_rewards_on_claim() {
+    let (mut context, character) = setup().await;
+
+    add_job_quest(&mut context, 1, fixed(&[WOOD, GOLD]), fixed(&[10, 30])).await;
+    complete_job_quest(&mut context, &character, 1).await;
+
+    start_forge(&mut context, &character, 1).await.unwrap();
+
+    let materials: CharacterMaterials = fetch(&mut context, character.character_materials).await;
+    assert_eq!(materials.raw[(WOOD - 1) as usize], 5);
+    assert_eq!(materials.raw[(GOLD - 1) as usize], 10);
+
+    let stats: Character = fetch(&mut context, character.character).await;
+    assert_eq!(stats.pool_stats.might, 1);
+    assert_eq!(stats.pool_stats.speed, 1);
+    assert_eq!(stats.pool_stats.intellect, 1);
+
+    // The recipe cooldown has not passed yet
+    let result = claim_forge(&mut context, &character, 1).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::NotAbleToClaimForgeRecipe));
+
+    warp_seconds(&mut context, 10).await;
+
+    claim_forge(&mut context, &character, 1).await.unwrap();
+
+    let materials: CharacterMaterials = fetch(&mut context, character.character_materials).await;
+    assert_eq!(materials.basic[(WOOD_PLANK - 1) as usize], 1);
+
+    let slots: CharacterSlots = fetch(&mut context, character.character_slots).await;
+    assert!(slots.forge.last_task_claimed);
+
+    // The reward can only be claimed once
+    let result = claim_forge(&mut context, &character, 1).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::NotAbleToClaimForgeRecipe));
+}
+
+#[tokio::test]
+async fn forge_requires_materials() {
+    let (mut context, character) = setup().await;
+
+    let result = start_forge(&mut context, &character, 1).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::NotEnoughResources));
+}
+
+#[tokio::test]
+async fn forge_slot_is_busy_until_claimed() {
+    let (mut context, character) = setup().await;
+
+    add_job_quest(&mut context, 1, fixed(&[WOOD, GOLD]), fixed(&[100, 100])).await;
+    complete_job_quest(&mut context, &character, 1).await;
+
+    start_forge(&mut context, &character, 1).await.unwrap();
+
+    warp_seconds(&mut context, 10).await;
+
+    let result = start_forge(&mut context, &character, 1).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::NotAbleToForgeRecipe));
+}
+
+#[tokio::test]
+async fn forge_rejects_another_character_materials() {
+    let (mut context, character) = setup().await;
+    let other = add_character(&mut context).await;
+
+    let mut accounts = forge_access(&context, &character, 1);
+    accounts.character_materials = other.character_materials;
+
+    let ix = instruction(accounts, ::arising::instruction::StartForge {});
+    let result = process(&mut context, &[ix], &[]).await;
+
+    assert_eq!(
+        error_code(result),
+        u32::from(anchor_lang::error::ErrorCode::ConstraintSeeds)
+    );
+}
+
+#[tokio::test]
+async fn forge_recipes_require_valid_materials() {
+    let (mut context, _) = setup().await;
+
+    let mut recipe = wood_plank_recipe();
+    recipe.id = 2;
+    recipe.materials = fixed(&[WOOD, 21]);
+
+    let ix = add_forge_recipe_instruction(&context, recipe);
+    let result = process(&mut context, &[ix], &[]).await;
+    assert_eq!(error_code(result), u32::from(ForgeError::InvalidMaterial));
+
+    // Unknown material types are rejected instead of skipped
+    let mut recipe = wood_plank_recipe();
+    recipe.id = 2;
+    recipe.materials_types[1] = 7;
+
+    let ix = add_forge_recipe_instruction(&context, recipe);
+    let result = process(&mut context, &[ix], &[]).await;
+    assert_eq!(error_code(result), u32::from(ForgeError::InvalidMaterial));
+
+    let mut recipe = wood_plank_recipe();
+    recipe.item_rewarded_type = ResourceType::Item as u16;
+
+    let accounts = ::arising::accounts::UpdateForgeRecipe {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        forge_recipe: forge_recipe_address(1),
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::UpdateForgeRecipe { data: recipe });
+    let result = process(&mut context, &[ix], &[]).await;
+    assert_eq!(error_code(result), u32::from(ForgeError::InvalidMaterial));
+}
+
+#[tokio::test]
+async fn forge_sacrifices_base_stats() {
+    let (mut context, character) = setup().await;
+
+    add_job_quest(&mut context, 1, fixed(&[WOOD, GOLD]), fixed(&[100, 100])).await;
+    complete_job_quest(&mut context, &character, 1).await;
+
+    let mut recipe = wood_plank_recipe();
+    recipe.id = 2;
+    recipe.stats_sacrificed = BaseStats { might: 2, speed: 0, intellect: 0 };
+    add_forge_recipe(&mut context, recipe).await;
+
+    start_forge(&mut context, &character, 2).await.unwrap();
+
+    let stats: Character = fetch(&mut context, character.character).await;
+    assert_eq!(stats.base_stats.might, 0);
+    assert_eq!(stats.pool_stats.might, 0);
+    assert_eq!(stats.base_stats.speed, 2);
+    assert_eq!(stats.sacrificed_points, 2);
+
+    warp_seconds(&mut context, 10).await;
+    claim_forge(&mut context, &character, 2).await.unwrap();
+
+    // No base might left to sacrifice
+    let result = start_forge(&mut context, &character, 2).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::NotEnoughBasePointsToSacrifice));
+
+    // The sacrificed points can't be assigned again
+    let result = assign_stats(&mut context, &character, BaseStats {
+        might: 5,
+        speed: 0,
+        intellect: 0,
+    }).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::NotEnoughAssignablePoints));
+}
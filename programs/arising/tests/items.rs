@@ -0,0 +1,74 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use ::arising::*;
+use common::*;
+use solana_program_test::*;
+use solana_sdk::signature::Signer;
+
+async fn setup() -> ProgramTestContext {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    return context;
+}
+
+#[tokio::test]
+async fn item_definitions_store_the_item_stats() {
+    let mut context = setup().await;
+
+    add_item_definition(&mut context, bone_dagger_definition()).await;
+
+    let definition: ItemDefinition = fetch(&mut context, item_definition_address(BONE_DAGGER)).await;
+    assert_eq!(definition.id, BONE_DAGGER);
+    assert_eq!(definition.item_type, ItemType::OneHanded as u16);
+    assert_eq!(definition.attributes_additions.atk, 1);
+    assert_eq!(definition.level_required, 0);
+
+    let mut data = bone_dagger_definition();
+    data.level_required = 3;
+
+    let accounts = ::arising::accounts::UpdateItemDefinition {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        item_definition: item_definition_address(BONE_DAGGER),
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::UpdateItemDefinition { data });
+    process(&mut context, &[ix], &[]).await.unwrap();
+
+    let definition: ItemDefinition = fetch(&mut context, item_definition_address(BONE_DAGGER)).await;
+    assert_eq!(definition.level_required, 3);
+
+    let config: Config = fetch(&mut context, config_address()).await;
+    assert_eq!(config.item_definitions, 1);
+}
+
+#[tokio::test]
+async fn item_definitions_require_a_valid_type() {
+    let mut context = setup().await;
+
+    let mut definition = bone_dagger_definition();
+    definition.item_type = (ItemType::Cape as u16) + 1;
+
+    let ix = add_item_definition_instruction(&context, definition);
+    let result = process(&mut context, &[ix], &[]).await;
+
+    assert_eq!(error_code(result), u32::from(ItemError::InvalidType));
+}
+
+#[tokio::test]
+async fn item_definitions_require_the_next_id() {
+    let mut context = setup().await;
+
+    let mut definition = bone_dagger_definition();
+    definition.id = 2;
+
+    let ix = add_item_definition_instruction(&context, definition);
+    let result = process(&mut context, &[ix], &[]).await;
+
+    assert_eq!(error_code(result), u32::from(ItemError::InvalidID));
+}
@@ -0,0 +1,313 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::error::ErrorCode;
+use anchor_spl::{ associated_token::get_associated_token_address, token::spl_token };
+use ::arising::*;
+use common::*;
+use solana_program_test::*;
+use solana_sdk::{
+    program_pack::Pack,
+    signature::{ Keypair, Signer },
+    system_instruction,
+    system_program,
+    sysvar,
+};
+
+const MATERIAL_MINT_PREFIX: &str = "arising_material_mint";
+const MATERIAL_AUTHORITY_PREFIX: &str = "arising_material_authority";
+
+fn material_mint_registry_address(material_type: u16, material: u32) -> Pubkey {
+    return Pubkey::find_program_address(
+        &[MATERIAL_MINT_PREFIX.as_bytes(), &material_type.to_le_bytes(), &material.to_le_bytes()],
+        &::arising::ID
+    ).0;
+}
+
+fn material_authority_address() -> Pubkey {
+    return Pubkey::find_program_address(&[MATERIAL_AUTHORITY_PREFIX.as_bytes()], &::arising::ID).0;
+}
+
+async fn add_material_mint(
+    context: &mut ProgramTestContext,
+    material_type: u16,
+    material: u32,
+    mint: &Keypair
+) -> std::result::Result<(), BanksClientError> {
+    let accounts = ::arising::accounts::AddMaterialMint {
+        payer: context.payer.pubkey(),
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        material_mint_registry: material_mint_registry_address(material_type, material),
+        material_mint: mint.pubkey(),
+        material_authority: material_authority_address(),
+        token_program: spl_token::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::AddMaterialMint {
+        material_type,
+        material,
+    });
+
+    return process(context, &[ix], &[mint]).await;
+}
+
+/// Creates the payer associated token account for the material mint.
+async fn create_material_token_account(context: &mut ProgramTestContext, mint: &Pubkey) -> Pubkey {
+    let payer = context.payer.pubkey();
+
+    let ix = spl_associated_token_account::instruction::create_associated_token_account(
+        &payer,
+        &payer,
+        mint,
+        &spl_token::ID
+    );
+
+    process(context, &[ix], &[]).await.unwrap();
+
+    return get_associated_token_address(&payer, mint);
+}
+
+fn material_access(
+    context: &ProgramTestContext,
+    character: &CharacterAccounts,
+    material_type: u16,
+    material: u32,
+    mint: &Pubkey,
+    token_account: &Pubkey
+) -> ::arising::accounts::MaterialAccess {
+    return ::arising::accounts::MaterialAccess {
+        payer: context.payer.pubkey(),
+        character: character.character,
+        character_materials: character.character_materials,
+        character_token_account: character.token_account,
+        config: config_address(),
+        material_mint_registry: material_mint_registry_address(material_type, material),
+        material_mint: *mint,
+        material_token_account: *token_account,
+        material_authority: material_authority_address(),
+        token_program: spl_token::ID,
+    };
+}
+
+async fn withdraw_materials(
+    context: &mut ProgramTestContext,
+    character: &CharacterAccounts,
+    mint: &Pubkey,
+    token_account: &Pubkey,
+    amount: u32
+) -> std::result::Result<(), BanksClientError> {
+    let raw = ResourceType::Raw as u16;
+    let accounts = material_access(context, character, raw, WOOD, mint, token_account);
+
+    let ix = instruction(accounts, ::arising::instruction::WithdrawMaterials {
+        material_type: raw,
+        material: WOOD,
+        amount,
+    });
+
+    return process(context, &[ix], &[]).await;
+}
+
+async fn deposit_materials(
+    context: &mut ProgramTestContext,
+    character: &CharacterAccounts,
+    mint: &Pubkey,
+    token_account: &Pubkey,
+    amount: u32
+) -> std::result::Result<(), BanksClientError> {
+    let raw = ResourceType::Raw as u16;
+    let accounts = material_access(context, character, raw, WOOD, mint, token_account);
+
+    let ix = instruction(accounts, ::arising::instruction::DepositMaterials {
+        material_type: raw,
+        material: WOOD,
+        amount,
+    });
+
+    return process(context, &[ix], &[]).await;
+}
+
+/// Initializes the program with a character holding 10 wood and a registered wood mint.
+async fn setup() -> (ProgramTestContext, CharacterAccounts, Pubkey, Pubkey) {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let character = add_character(&mut context).await;
+    assign_stats(&mut context, &character, BaseStats { might: 2, speed: 2, intellect: 2 }).await
+        .unwrap();
+
+    add_job_quest(&mut context, 1, fixed(&[WOOD, GOLD]), fixed(&[10, 30])).await;
+    complete_job_quest(&mut context, &character, 1).await;
+
+    let mint = Keypair::new();
+    add_material_mint(&mut context, ResourceType::Raw as u16, WOOD, &mint).await.unwrap();
+
+    let token_account = create_material_token_account(&mut context, &mint.pubkey()).await;
+
+    return (context, character, mint.pubkey(), token_account);
+}
+
+#[tokio::test]
+async fn withdraw_and_deposit_materials() {
+    let (mut context, character, mint, token_account) = setup().await;
+
+    let registry: MaterialMint = fetch(
+        &mut context,
+        material_mint_registry_address(ResourceType::Raw as u16, WOOD)
+    ).await;
+    assert_eq!(registry.mint, mint);
+
+    withdraw_materials(&mut context, &character, &mint, &token_account, 4).await.unwrap();
+
+    let materials: CharacterMaterials = fetch(&mut context, character.character_materials).await;
+    assert_eq!(materials.raw[(WOOD - 1) as usize], 6);
+
+    let token = fetch_token_account(&mut context, token_account).await;
+    assert_eq!(token.amount, 4);
+
+    deposit_materials(&mut context, &character, &mint, &token_account, 3).await.unwrap();
+
+    let materials: CharacterMaterials = fetch(&mut context, character.character_materials).await;
+    assert_eq!(materials.raw[(WOOD - 1) as usize], 9);
+
+    let token = fetch_token_account(&mut context, token_account).await;
+    assert_eq!(token.amount, 1);
+
+    let mint_state = fetch_mint(&mut context, mint).await;
+    assert_eq!(mint_state.supply, 1);
+}
+
+#[tokio::test]
+async fn withdraw_requires_the_materials() {
+    let (mut context, character, mint, token_account) = setup().await;
+
+    let result = withdraw_materials(&mut context, &character, &mint, &token_account, 11).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::NotEnoughResources));
+
+    let result = withdraw_materials(&mut context, &character, &mint, &token_account, 0).await;
+    assert_eq!(error_code(result), u32::from(MaterialError::InvalidAmount));
+
+    // Can't deposit more than the tokens held
+    let result = deposit_materials(&mut context, &character, &mint, &token_account, 1).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::NotEnoughResources));
+}
+
+#[tokio::test]
+async fn materials_use_the_registered_mint() {
+    let (mut context, character, _, _) = setup().await;
+
+    let (other_mint, other_token_account) = mint_tokens(&mut context, 5).await;
+
+    let result = deposit_materials(
+        &mut context,
+        &character,
+        &other_mint,
+        &other_token_account,
+        5
+    ).await;
+    assert_eq!(error_code(result), u32::from(MaterialError::InvalidMint));
+}
+
+#[tokio::test]
+async fn materials_use_the_payer_associated_token_account() {
+    let (mut context, character, mint, _) = setup().await;
+
+    // A token account of the payer for the material mint that isn't the associated one
+    let token_account = Keypair::new();
+    let payer = context.payer.pubkey();
+    let rent = context.banks_client.get_rent().await.unwrap();
+
+    let instructions = [
+        system_instruction::create_account(
+            &payer,
+            &token_account.pubkey(),
+            rent.minimum_balance(spl_token::state::Account::LEN),
+            spl_token::state::Account::LEN as u64,
+            &spl_token::ID
+        ),
+        spl_token::instruction
+            ::initialize_account(&spl_token::ID, &token_account.pubkey(), &mint, &payer)
+            .unwrap(),
+    ];
+
+    process(&mut context, &instructions, &[&token_account]).await.unwrap();
+
+    let result = withdraw_materials(&mut context, &character, &mint, &token_account.pubkey(), 1).await;
+    assert_eq!(error_code(result), u32::from(ErrorCode::ConstraintAssociated));
+}
+
+#[tokio::test]
+async fn material_mints_require_a_valid_material() {
+    let (mut context, _, _, _) = setup().await;
+
+    let result = add_material_mint(&mut context, ResourceType::Raw as u16, 21, &Keypair::new()).await;
+    assert_eq!(error_code(result), u32::from(MaterialError::InvalidMaterial));
+
+    let result = add_material_mint(&mut context, ResourceType::Item as u16, 1, &Keypair::new()).await;
+    assert_eq!(error_code(result), u32::from(MaterialError::InvalidMaterial));
+}
+
+async fn transfer_materials(
+    context: &mut ProgramTestContext,
+    from: &CharacterAccounts,
+    to: &CharacterAccounts,
+    amount: u32
+) -> std::result::Result<(), BanksClientError> {
+    let accounts = ::arising::accounts::TransferMaterials {
+        payer: context.payer.pubkey(),
+        character: from.character,
+        character_materials: from.character_materials,
+        character_token_account: from.token_account,
+        config: config_address(),
+        destination_character: to.character,
+        destination_character_materials: to.character_materials,
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::TransferMaterials {
+        material_type: ResourceType::Raw as u16,
+        material: WOOD,
+        amount,
+    });
+
+    return process(context, &[ix], &[]).await;
+}
+
+#[tokio::test]
+async fn transfer_materials_between_characters() {
+    let (mut context, character, _, _) = setup().await;
+
+    let alt = add_character(&mut context).await;
+
+    transfer_materials(&mut context, &character, &alt, 7).await.unwrap();
+
+    let materials: CharacterMaterials = fetch(&mut context, character.character_materials).await;
+    assert_eq!(materials.raw[(WOOD - 1) as usize], 3);
+
+    let materials: CharacterMaterials = fetch(&mut context, alt.character_materials).await;
+    assert_eq!(materials.raw[(WOOD - 1) as usize], 7);
+
+    let result = transfer_materials(&mut context, &character, &alt, 4).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::NotEnoughResources));
+
+    let result = transfer_materials(&mut context, &character, &character, 1).await;
+    assert_eq!(error_code(result), u32::from(MaterialError::SameCharacter));
+}
+
+#[tokio::test]
+async fn transfer_materials_requires_the_source_owner() {
+    let (mut context, character, _, _) = setup().await;
+
+    let alt = add_character(&mut context).await;
+
+    let from = CharacterAccounts { token_account: alt.token_account, ..character };
+
+    let result = transfer_materials(&mut context, &from, &alt, 1).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidOwner));
+}
@@ -0,0 +1,157 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use ::arising::*;
+use common::*;
+use solana_program_test::*;
+use solana_sdk::{ instruction::Instruction, signature::{ Keypair, Signer }, system_program };
+
+const OPERATOR_PREFIX: &str = "arising_operator";
+
+fn operator_address(operator: &Pubkey) -> Pubkey {
+    return Pubkey::find_program_address(
+        &[OPERATOR_PREFIX.as_bytes(), &operator.to_bytes()],
+        &::arising::ID
+    ).0;
+}
+
+async fn add_operator(
+    context: &mut ProgramTestContext,
+    operator: &Pubkey,
+    roles: u16
+) -> std::result::Result<(), BanksClientError> {
+    let accounts = ::arising::accounts::AddOperator {
+        payer: context.payer.pubkey(),
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: operator_address(operator),
+        system_program: system_program::ID,
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::AddOperator {
+        operator_key: *operator,
+        roles,
+    });
+
+    return process(context, &[ix], &[]).await;
+}
+
+async fn update_operator(
+    context: &mut ProgramTestContext,
+    operator: &Pubkey,
+    roles: u16
+) -> std::result::Result<(), BanksClientError> {
+    let accounts = ::arising::accounts::UpdateOperator {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: operator_address(operator),
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::UpdateOperator { roles });
+
+    return process(context, &[ix], &[]).await;
+}
+
+/// Adds a forge recipe signed by the operator, the payer only funds the account.
+fn operator_add_forge_recipe_instruction(
+    context: &ProgramTestContext,
+    operator: &Keypair,
+    with_operator_account: bool,
+    recipe: Recipe
+) -> Instruction {
+    let id = recipe.id;
+
+    let accounts = ::arising::accounts::AddForgeRecipe {
+        payer: context.payer.pubkey(),
+        authority: operator.pubkey(),
+        config: config_address(),
+        operator: if with_operator_account {
+            Some(operator_address(&operator.pubkey()))
+        } else {
+            None
+        },
+        forge_recipe: forge_recipe_address(id),
+        system_program: system_program::ID,
+    };
+
+    return instruction(accounts, ::arising::instruction::AddForgeRecipe {
+        _bump: 0,
+        id,
+        data: recipe,
+    });
+}
+
+fn operator_set_paused_instruction(operator: &Keypair) -> Instruction {
+    let accounts = ::arising::accounts::SetPause {
+        authority: operator.pubkey(),
+        config: config_address(),
+        operator: Some(operator_address(&operator.pubkey())),
+    };
+
+    return instruction(accounts, ::arising::instruction::SetPaused { paused: true });
+}
+
+#[tokio::test]
+async fn operators_only_use_their_roles() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let operator = Keypair::new();
+    add_operator(&mut context, &operator.pubkey(), Role::Content as u16).await.unwrap();
+
+    // The operator account is needed to prove the role
+    let ix = operator_add_forge_recipe_instruction(&context, &operator, false, wood_plank_recipe());
+    let result = process(&mut context, &[ix], &[&operator]).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidAuthority));
+
+    let ix = operator_add_forge_recipe_instruction(&context, &operator, true, wood_plank_recipe());
+    process(&mut context, &[ix], &[&operator]).await.unwrap();
+
+    let config: Config = fetch(&mut context, config_address()).await;
+    assert_eq!(config.forge_recipes, 1);
+
+    // Content operators can't pause the game
+    let ix = operator_set_paused_instruction(&operator);
+    let result = process(&mut context, &[ix], &[&operator]).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidAuthority));
+
+    update_operator(
+        &mut context,
+        &operator.pubkey(),
+        (Role::Content as u16) | (Role::Pause as u16)
+    ).await.unwrap();
+
+    let ix = operator_set_paused_instruction(&operator);
+    process(&mut context, &[ix], &[&operator]).await.unwrap();
+
+    let config: Config = fetch(&mut context, config_address()).await;
+    assert!(config.paused);
+}
+
+#[tokio::test]
+async fn operator_roles_are_validated() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let operator = Keypair::new();
+
+    let result = add_operator(&mut context, &operator.pubkey(), ALL_ROLES + 1).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidRoles));
+
+    add_operator(&mut context, &operator.pubkey(), ALL_ROLES).await.unwrap();
+
+    let stored: Operator = fetch(&mut context, operator_address(&operator.pubkey())).await;
+    assert_eq!(stored.operator, operator.pubkey());
+    assert_eq!(stored.roles, ALL_ROLES);
+
+    // Revoking every role removes the access
+    update_operator(&mut context, &operator.pubkey(), 0).await.unwrap();
+
+    let ix = operator_set_paused_instruction(&operator);
+    let result = process(&mut context, &[ix], &[&operator]).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidAuthority));
+}
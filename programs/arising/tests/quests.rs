@@ -0,0 +1,332 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use anchor_lang::prelude::Clock;
+use ::arising::*;
+use common::*;
+use solana_program_test::*;
+use solana_sdk::{ hash::hashv, signature::Signer };
+
+const REVEAL: [u8; 32] = [3u8; 32];
+
+fn set_experience_event_instruction(
+    context: &ProgramTestContext,
+    multiplier: u64,
+    start: u64,
+    end: u64
+) -> solana_sdk::instruction::Instruction {
+    let accounts = ::arising::accounts::SetExperienceMultiplier {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+    };
+
+    return instruction(accounts, ::arising::instruction::SetExperienceEvent {
+        multiplier,
+        start,
+        end,
+    });
+}
+
+/// Adds and enables a raid against a mob the character always beats.
+async fn add_raid_quest(
+    context: &mut ProgramTestContext,
+    id: u32,
+    mob_experience: u32,
+    materials_reward: [u32; 10],
+    materials_amounts: [u32; 10]
+) {
+    let mut quest = job_quest(id, materials_reward, materials_amounts);
+    quest.quest_type = QuestType::Raid as u16;
+    quest.mob_experience = mob_experience;
+
+    let add = add_quest_instruction(context, quest);
+
+    let accounts = ::arising::accounts::UpdateQuest {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        quest: quest_address(id),
+    };
+
+    let enable = instruction(accounts, ::arising::instruction::UpdateQuestAvailability {
+        available: true,
+    });
+
+    process(context, &[add, enable], &[]).await.unwrap();
+}
+
+async fn complete_raid_quest(context: &mut ProgramTestContext, character: &CharacterAccounts, id: u32) {
+    let start = instruction(quest_access(context, character, id), ::arising::instruction::StartQuest {
+        commitment: hashv(&[&REVEAL]).to_bytes(),
+    });
+    process(context, &[start], &[]).await.unwrap();
+
+    warp_slots(context, 5).await;
+    warp_seconds(context, 10).await;
+
+    let claim = instruction(quest_access(context, character, id), ::arising::instruction::ClaimQuest {
+        reveal: REVEAL,
+    });
+    process(context, &[claim], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn quests_require_valid_materials() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let quest = job_quest(1, fixed(&[WOOD, 21]), fixed(&[10, 10]));
+
+    let ix = add_quest_instruction(&context, quest);
+    let result = process(&mut context, &[ix], &[]).await;
+    assert_eq!(error_code(result), u32::from(QuestError::InvalidMaterial));
+}
+
+#[tokio::test]
+async fn claims_only_reward_the_started_quest() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let character = add_character(&mut context).await;
+    assign_stats(&mut context, &character, BaseStats { might: 2, speed: 2, intellect: 2 }).await
+        .unwrap();
+
+    add_job_quest(&mut context, 1, fixed(&[WOOD]), fixed(&[1])).await;
+    add_job_quest(&mut context, 2, fixed(&[GOLD]), fixed(&[100])).await;
+
+    let start = instruction(quest_access(&context, &character, 1), ::arising::instruction::StartQuest {
+        commitment: [0; 32],
+    });
+    process(&mut context, &[start], &[]).await.unwrap();
+
+    warp_seconds(&mut context, 10).await;
+
+    let claim = instruction(quest_access(&context, &character, 2), ::arising::instruction::ClaimQuest {
+        reveal: [0; 32],
+    });
+    let result = process(&mut context, &[claim], &[]).await;
+    assert_eq!(error_code(result), u32::from(QuestError::InvalidID));
+
+    let claim = instruction(quest_access(&context, &character, 1), ::arising::instruction::ClaimQuest {
+        reveal: [0; 32],
+    });
+    process(&mut context, &[claim], &[]).await.unwrap();
+
+    let materials: CharacterMaterials = fetch(&mut context, character.character_materials).await;
+    assert_eq!(materials.raw[(WOOD - 1) as usize], 1);
+    assert_eq!(materials.raw[(GOLD - 1) as usize], 0);
+}
+
+#[tokio::test]
+async fn raid_outcomes_dont_depend_on_the_claim_slot() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let first = add_character(&mut context).await;
+    let second = add_character(&mut context).await;
+
+    let stats = BaseStats { might: 2, speed: 2, intellect: 2 };
+    assign_stats(&mut context, &first, stats.clone()).await.unwrap();
+    assign_stats(&mut context, &second, stats).await.unwrap();
+
+    add_raid_quest(&mut context, 1, 100, fixed(&[WOOD]), fixed(&[1000])).await;
+
+    // Both raids are committed in the same slot with the same secret
+    let commitment = hashv(&[&REVEAL]).to_bytes();
+    let start_first = instruction(quest_access(&context, &first, 1), ::arising::instruction::StartQuest {
+        commitment,
+    });
+    let start_second = instruction(quest_access(&context, &second, 1), ::arising::instruction::StartQuest {
+        commitment,
+    });
+    process(&mut context, &[start_first, start_second], &[]).await.unwrap();
+
+    let first_slots: CharacterSlots = fetch(&mut context, first.character_slots).await;
+    let second_slots: CharacterSlots = fetch(&mut context, second.character_slots).await;
+    assert_eq!(first_slots.quest.commitment_slot, second_slots.quest.commitment_slot);
+
+    warp_slots(&mut context, 5).await;
+    warp_seconds(&mut context, 10).await;
+
+    let claim = instruction(quest_access(&context, &first, 1), ::arising::instruction::ClaimQuest {
+        reveal: REVEAL,
+    });
+    process(&mut context, &[claim], &[]).await.unwrap();
+
+    // Claiming later doesn't pick a different slot hash
+    warp_slots(&mut context, 40).await;
+    warp_seconds(&mut context, 10).await;
+
+    let claim = instruction(quest_access(&context, &second, 1), ::arising::instruction::ClaimQuest {
+        reveal: REVEAL,
+    });
+    process(&mut context, &[claim], &[]).await.unwrap();
+
+    let first_materials: CharacterMaterials = fetch(&mut context, first.character_materials).await;
+    let second_materials: CharacterMaterials = fetch(&mut context, second.character_materials).await;
+    assert_eq!(first_materials.raw, second_materials.raw);
+    assert!(first_materials.raw[(WOOD - 1) as usize] >= 500);
+
+    let first_stats: Character = fetch(&mut context, first.character).await;
+    let second_stats: Character = fetch(&mut context, second.character).await;
+    assert_eq!(first_stats.experience, second_stats.experience);
+}
+
+#[tokio::test]
+async fn expired_raids_settle_as_lost() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let character = add_character(&mut context).await;
+    assign_stats(&mut context, &character, BaseStats { might: 2, speed: 2, intellect: 2 }).await
+        .unwrap();
+
+    add_raid_quest(&mut context, 1, 100, fixed(&[WOOD]), fixed(&[1000])).await;
+
+    let start = instruction(quest_access(&context, &character, 1), ::arising::instruction::StartQuest {
+        commitment: hashv(&[&REVEAL]).to_bytes(),
+    });
+    process(&mut context, &[start], &[]).await.unwrap();
+
+    expire_slot_hashes(&mut context).await;
+    warp_seconds(&mut context, 10).await;
+
+    let claim = instruction(quest_access(&context, &character, 1), ::arising::instruction::ClaimQuest {
+        reveal: REVEAL,
+    });
+    process(&mut context, &[claim], &[]).await.unwrap();
+
+    let materials: CharacterMaterials = fetch(&mut context, character.character_materials).await;
+    assert_eq!(materials.raw[(WOOD - 1) as usize], 0);
+
+    let stats: Character = fetch(&mut context, character.character).await;
+    assert_eq!(stats.experience, 0);
+
+    let slots: CharacterSlots = fetch(&mut context, character.character_slots).await;
+    assert!(slots.quest.last_task_claimed);
+
+    // The quest slot is free again
+    complete_raid_quest(&mut context, &character, 1).await;
+
+    let stats: Character = fetch(&mut context, character.character).await;
+    assert_eq!(stats.experience, 100);
+}
+
+#[tokio::test]
+async fn stored_slot_hashes_outlive_the_slot_hashes_window() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let character = add_character(&mut context).await;
+    assign_stats(&mut context, &character, BaseStats { might: 2, speed: 2, intellect: 2 }).await
+        .unwrap();
+
+    add_raid_quest(&mut context, 1, 100, fixed(&[WOOD]), fixed(&[1000])).await;
+
+    let start = instruction(quest_access(&context, &character, 1), ::arising::instruction::StartQuest {
+        commitment: hashv(&[&REVEAL]).to_bytes(),
+    });
+    process(&mut context, &[start], &[]).await.unwrap();
+
+    // The slot after the commitment isn't hashed yet
+    let store = store_slot_hashes_instruction(&character);
+    let result = process(&mut context, &[store], &[]).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::SlotHashNotStored));
+
+    warp_slots(&mut context, 5).await;
+
+    let store = store_slot_hashes_instruction(&character);
+    process(&mut context, &[store], &[]).await.unwrap();
+
+    let slots: CharacterSlots = fetch(&mut context, character.character_slots).await;
+    assert!(slots.quest.slot_hash_stored);
+
+    expire_slot_hashes(&mut context).await;
+    warp_seconds(&mut context, 10).await;
+
+    let claim = instruction(quest_access(&context, &character, 1), ::arising::instruction::ClaimQuest {
+        reveal: REVEAL,
+    });
+    process(&mut context, &[claim], &[]).await.unwrap();
+
+    let materials: CharacterMaterials = fetch(&mut context, character.character_materials).await;
+    assert!(materials.raw[(WOOD - 1) as usize] >= 500);
+
+    let stats: Character = fetch(&mut context, character.character).await;
+    assert_eq!(stats.experience, 100);
+}
+
+#[tokio::test]
+async fn experience_events_multiply_raid_experience() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let character = add_character(&mut context).await;
+    assign_stats(&mut context, &character, BaseStats { might: 2, speed: 2, intellect: 2 }).await
+        .unwrap();
+
+    add_raid_quest(&mut context, 1, 100, fixed(&[]), fixed(&[])).await;
+
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let now = clock.unix_timestamp as u64;
+
+    let ix = set_experience_event_instruction(&context, 3, now, now + 60);
+    process(&mut context, &[ix], &[]).await.unwrap();
+
+    complete_raid_quest(&mut context, &character, 1).await;
+
+    let stats: Character = fetch(&mut context, character.character).await;
+    assert_eq!(stats.experience, 300);
+
+    // Once the event is over the base multiplier applies again
+    warp_seconds(&mut context, 120).await;
+    complete_raid_quest(&mut context, &character, 1).await;
+
+    let stats: Character = fetch(&mut context, character.character).await;
+    assert_eq!(stats.experience, 400);
+}
+
+#[tokio::test]
+async fn experience_multipliers_are_validated() {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let accounts = ::arising::accounts::SetExperienceMultiplier {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::SetExperienceMultiplier {
+        multiplier: 0,
+    });
+    let result = process(&mut context, &[ix], &[]).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidExperienceMultiplier));
+
+    let ix = set_experience_event_instruction(&context, 2, 100, 100);
+    let result = process(&mut context, &[ix], &[]).await;
+    assert_eq!(error_code(result), u32::from(ArisingError::InvalidExperienceEvent));
+
+    let accounts = ::arising::accounts::SetExperienceMultiplier {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::SetExperienceMultiplier {
+        multiplier: 2,
+    });
+    process(&mut context, &[ix], &[]).await.unwrap();
+
+    let config: Config = fetch(&mut context, config_address()).await;
+    assert_eq!(config.experience_multiplier, 2);
+}
@@ -0,0 +1,358 @@
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use ::arising::*;
+use common::*;
+use solana_program_test::*;
+use anchor_spl::token::spl_token::{ self, state::AccountState };
+use solana_sdk::{ hash::hashv, signature::{ Keypair, Signer }, system_program, sysvar };
+
+const UPGRADE_RECIPE_PREFIX: &str = "arising_upgrade_recipe";
+
+const REVEAL: [u8; 32] = [7u8; 32];
+
+fn upgrade_recipe_address(id: u32) -> Pubkey {
+    return Pubkey::find_program_address(
+        &[UPGRADE_RECIPE_PREFIX.as_bytes(), &id.to_le_bytes()],
+        &::arising::ID
+    ).0;
+}
+
+fn sharpen_recipe(id: u32, success_rate: u16) -> UpgradeRecipe {
+    return UpgradeRecipe {
+        id,
+        name: "Sharpen a Bone Dagger".to_string(),
+        item_id: BONE_DAGGER,
+        tier_required: 0,
+        materials: fixed(&[GOLD]),
+        materials_amounts: fixed(&[5]),
+        materials_types: [ResourceType::Raw as u16, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        stats_required: BaseStats::default(),
+        cooldown: 2,
+        level_required: 0,
+        success_rate,
+        stats_bonus: BaseStats { might: 1, speed: 0, intellect: 0 },
+        attributes_bonus: BaseAttributes { atk: 3, ..BaseAttributes::default() },
+        available: false,
+    };
+}
+
+async fn add_upgrade_recipe(context: &mut ProgramTestContext, recipe: UpgradeRecipe) {
+    let id = recipe.id;
+
+    let accounts = ::arising::accounts::AddUpgradeRecipe {
+        payer: context.payer.pubkey(),
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        upgrade_recipe: upgrade_recipe_address(id),
+        system_program: system_program::ID,
+    };
+
+    let add = instruction(accounts, ::arising::instruction::AddUpgradeRecipe {
+        _bump: 0,
+        id,
+        data: recipe,
+    });
+
+    let accounts = ::arising::accounts::UpdateUpgradeRecipe {
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        upgrade_recipe: upgrade_recipe_address(id),
+    };
+
+    let enable = instruction(accounts, ::arising::instruction::UpdateUpgradeRecipeAvailability {
+        available: true,
+    });
+
+    process(context, &[add, enable], &[]).await.unwrap();
+}
+
+fn upgrade_access(
+    context: &ProgramTestContext,
+    character: &CharacterAccounts,
+    item_mint: &Pubkey,
+    recipe_id: u32
+) -> ::arising::accounts::UpgradeAccess {
+    return ::arising::accounts::UpgradeAccess {
+        payer: context.payer.pubkey(),
+        character: character.character,
+        character_materials: character.character_materials,
+        character_slots: character.character_slots,
+        character_token_account: character.token_account,
+        config: config_address(),
+        upgrade_recipe: upgrade_recipe_address(recipe_id),
+        item: item_address(item_mint),
+        item_mint: *item_mint,
+        item_token_account: item_token_address(context, item_mint),
+        item_authority: item_authority_address(),
+        slot_hashes: sysvar::slot_hashes::ID,
+        token_program: spl_token::ID,
+    };
+}
+
+async fn start_upgrade(
+    context: &mut ProgramTestContext,
+    character: &CharacterAccounts,
+    item_mint: &Pubkey,
+    recipe_id: u32
+) -> std::result::Result<(), BanksClientError> {
+    let ix = instruction(upgrade_access(context, character, item_mint, recipe_id), ::arising::instruction::StartUpgrade {
+        commitment: hashv(&[&REVEAL]).to_bytes(),
+    });
+
+    return process(context, &[ix], &[]).await;
+}
+
+async fn claim_upgrade(
+    context: &mut ProgramTestContext,
+    character: &CharacterAccounts,
+    item_mint: &Pubkey,
+    recipe_id: u32,
+    reveal: [u8; 32]
+) -> std::result::Result<(), BanksClientError> {
+    let ix = instruction(upgrade_access(context, character, item_mint, recipe_id), ::arising::instruction::ClaimUpgrade {
+        reveal,
+    });
+
+    return process(context, &[ix], &[]).await;
+}
+
+/// Initializes the program with a character owning a crafted bone dagger and gold left to upgrade it.
+async fn setup() -> (ProgramTestContext, CharacterAccounts, Pubkey) {
+    let mut context = program_test().start_with_context().await;
+
+    initialize(&mut context).await;
+
+    let character = add_character(&mut context).await;
+    assign_stats(&mut context, &character, BaseStats { might: 2, speed: 2, intellect: 2 }).await
+        .unwrap();
+
+    add_job_quest(&mut context, 1, fixed(&[WOOD, GOLD]), fixed(&[10, 30])).await;
+    complete_job_quest(&mut context, &character, 1).await;
+
+    add_forge_recipe(&mut context, wood_plank_recipe()).await;
+    start_forge(&mut context, &character, 1).await.unwrap();
+    warp_seconds(&mut context, 10).await;
+    claim_forge(&mut context, &character, 1).await.unwrap();
+
+    add_item_definition(&mut context, bone_dagger_definition()).await;
+    add_craft_recipe(&mut context, bone_dagger_recipe()).await;
+    start_craft(&mut context, &character, 1).await.unwrap();
+    warp_seconds(&mut context, 10).await;
+    let dagger = Keypair::new();
+    claim_craft(&mut context, &character, 1, &dagger).await.unwrap();
+
+    return (context, character, dagger.pubkey());
+}
+
+/// Lets the upgrade cooldown pass and produces slot hashes after the commitment.
+async fn wait_upgrade(context: &mut ProgramTestContext) {
+    warp_slots(context, 5).await;
+    warp_seconds(context, 10).await;
+}
+
+#[tokio::test]
+async fn upgrade_raises_tier_and_bonuses() {
+    let (mut context, character, dagger) = setup().await;
+
+    add_upgrade_recipe(&mut context, sharpen_recipe(1, UPGRADE_RATE_PRECISION)).await;
+
+    start_upgrade(&mut context, &character, &dagger, 1).await.unwrap();
+
+    let item: ItemInstance = fetch(&mut context, item_address(&dagger)).await;
+    assert!(item.upgrading);
+
+    // The item token can't be moved while upgrading
+    let token_address = item_token_address(&context, &dagger);
+    let token_account = fetch_token_account(&mut context, token_address).await;
+    assert_eq!(token_account.state, AccountState::Frozen);
+
+    let materials: CharacterMaterials = fetch(&mut context, character.character_materials).await;
+    assert_eq!(materials.raw[(GOLD - 1) as usize], 5);
+
+    // The recipe cooldown has not passed yet
+    let result = claim_upgrade(&mut context, &character, &dagger, 1, REVEAL).await;
+    assert_eq!(error_code(result), u32::from(CharacterError::NotAbleToClaimUpgrade));
+
+    wait_upgrade(&mut context).await;
+
+    claim_upgrade(&mut context, &character, &dagger, 1, REVEAL).await.unwrap();
+
+    let item: ItemInstance = fetch(&mut context, item_address(&dagger)).await;
+    assert_eq!(item.tier, 1);
+    assert_eq!(item.stats_bonus.might, 1);
+    assert_eq!(item.attributes_bonus.atk, 3);
+    assert!(!item.upgrading);
+
+    let token_account = fetch_token_account(&mut context, token_address).await;
+    assert_eq!(token_account.state, AccountState::Initialized);
+
+    let slots: CharacterSlots = fetch(&mut context, character.character_slots).await;
+    assert!(slots.upgrade.last_task_claimed);
+
+    // The recipe only applies to tier 0 items
+    let result = start_upgrade(&mut context, &character, &dagger, 1).await;
+    assert_eq!(error_code(result), u32::from(UpgradeError::InvalidItem));
+}
+
+#[tokio::test]
+async fn failed_upgrade_keeps_the_item() {
+    let (mut context, character, dagger) = setup().await;
+
+    add_upgrade_recipe(&mut context, sharpen_recipe(1, 0)).await;
+
+    start_upgrade(&mut context, &character, &dagger, 1).await.unwrap();
+    wait_upgrade(&mut context).await;
+    claim_upgrade(&mut context, &character, &dagger, 1, REVEAL).await.unwrap();
+
+    let item: ItemInstance = fetch(&mut context, item_address(&dagger)).await;
+    assert_eq!(item.tier, 0);
+    assert_eq!(item.stats_bonus.might, 0);
+    assert!(!item.upgrading);
+
+    let materials: CharacterMaterials = fetch(&mut context, character.character_materials).await;
+    assert_eq!(materials.raw[(GOLD - 1) as usize], 5);
+}
+
+#[tokio::test]
+async fn items_being_upgraded_cant_be_upgraded_again() {
+    let (mut context, character, dagger) = setup().await;
+
+    add_upgrade_recipe(&mut context, sharpen_recipe(1, UPGRADE_RATE_PRECISION)).await;
+
+    start_upgrade(&mut context, &character, &dagger, 1).await.unwrap();
+
+    // Another character of the same owner has a free upgrade slot
+    let other = add_character(&mut context).await;
+    assign_stats(&mut context, &other, BaseStats { might: 2, speed: 2, intellect: 2 }).await.unwrap();
+    complete_job_quest(&mut context, &other, 1).await;
+
+    let result = start_upgrade(&mut context, &other, &dagger, 1).await;
+    assert_eq!(error_code(result), u32::from(ItemError::ItemLocked));
+}
+
+#[tokio::test]
+async fn expired_upgrades_fail_and_release_the_item() {
+    let (mut context, character, dagger) = setup().await;
+
+    add_upgrade_recipe(&mut context, sharpen_recipe(1, UPGRADE_RATE_PRECISION)).await;
+
+    start_upgrade(&mut context, &character, &dagger, 1).await.unwrap();
+
+    expire_slot_hashes(&mut context).await;
+    warp_seconds(&mut context, 10).await;
+
+    claim_upgrade(&mut context, &character, &dagger, 1, REVEAL).await.unwrap();
+
+    let item: ItemInstance = fetch(&mut context, item_address(&dagger)).await;
+    assert_eq!(item.tier, 0);
+    assert!(!item.upgrading);
+
+    let token_address = item_token_address(&context, &dagger);
+    let token_account = fetch_token_account(&mut context, token_address).await;
+    assert_eq!(token_account.state, AccountState::Initialized);
+
+    let slots: CharacterSlots = fetch(&mut context, character.character_slots).await;
+    assert!(slots.upgrade.last_task_claimed);
+    assert_eq!(slots.upgrade.item, Pubkey::default());
+}
+
+#[tokio::test]
+async fn stored_slot_hashes_settle_expired_upgrades() {
+    let (mut context, character, dagger) = setup().await;
+
+    add_upgrade_recipe(&mut context, sharpen_recipe(1, UPGRADE_RATE_PRECISION)).await;
+
+    start_upgrade(&mut context, &character, &dagger, 1).await.unwrap();
+    warp_slots(&mut context, 5).await;
+
+    let store = store_slot_hashes_instruction(&character);
+    process(&mut context, &[store], &[]).await.unwrap();
+
+    expire_slot_hashes(&mut context).await;
+    warp_seconds(&mut context, 10).await;
+
+    claim_upgrade(&mut context, &character, &dagger, 1, REVEAL).await.unwrap();
+
+    let item: ItemInstance = fetch(&mut context, item_address(&dagger)).await;
+    assert_eq!(item.tier, 1);
+    assert!(!item.upgrading);
+}
+
+#[tokio::test]
+async fn upgrade_claim_requires_the_committed_reveal() {
+    let (mut context, character, dagger) = setup().await;
+
+    add_upgrade_recipe(&mut context, sharpen_recipe(1, UPGRADE_RATE_PRECISION)).await;
+
+    start_upgrade(&mut context, &character, &dagger, 1).await.unwrap();
+    wait_upgrade(&mut context).await;
+
+    let result = claim_upgrade(&mut context, &character, &dagger, 1, [8u8; 32]).await;
+    assert_eq!(error_code(result), u32::from(UpgradeError::InvalidReveal));
+}
+
+#[tokio::test]
+async fn upgrade_claim_requires_the_locked_item() {
+    let (mut context, character, dagger) = setup().await;
+
+    // Refresh the pool to craft a second dagger
+    warp_seconds(&mut context, 86_401).await;
+    let ix = instruction(character_refresh(&context, &character), ::arising::instruction::PerformRefresh {});
+    process(&mut context, &[ix], &[]).await.unwrap();
+
+    complete_job_quest(&mut context, &character, 1).await;
+    start_forge(&mut context, &character, 1).await.unwrap();
+    warp_seconds(&mut context, 10).await;
+    claim_forge(&mut context, &character, 1).await.unwrap();
+    start_craft(&mut context, &character, 1).await.unwrap();
+    warp_seconds(&mut context, 10).await;
+    let other = Keypair::new();
+    claim_craft(&mut context, &character, 1, &other).await.unwrap();
+
+    add_upgrade_recipe(&mut context, sharpen_recipe(1, UPGRADE_RATE_PRECISION)).await;
+
+    start_upgrade(&mut context, &character, &dagger, 1).await.unwrap();
+    wait_upgrade(&mut context).await;
+
+    let slots: CharacterSlots = fetch(&mut context, character.character_slots).await;
+    assert_eq!(slots.upgrade.item, dagger);
+
+    let result = claim_upgrade(&mut context, &character, &other.pubkey(), 1, REVEAL).await;
+    assert_eq!(error_code(result), u32::from(UpgradeError::InvalidLockedItem));
+
+    claim_upgrade(&mut context, &character, &dagger, 1, REVEAL).await.unwrap();
+
+    let slots: CharacterSlots = fetch(&mut context, character.character_slots).await;
+    assert_eq!(slots.upgrade.item, Pubkey::default());
+
+    let item: ItemInstance = fetch(&mut context, item_address(&other.pubkey())).await;
+    assert_eq!(item.tier, 0);
+}
+
+#[tokio::test]
+async fn upgrade_recipes_reject_rates_above_precision() {
+    let (mut context, _, _) = setup().await;
+
+    let accounts = ::arising::accounts::AddUpgradeRecipe {
+        payer: context.payer.pubkey(),
+        authority: context.payer.pubkey(),
+        config: config_address(),
+        operator: None,
+        upgrade_recipe: upgrade_recipe_address(1),
+        system_program: system_program::ID,
+    };
+
+    let ix = instruction(accounts, ::arising::instruction::AddUpgradeRecipe {
+        _bump: 0,
+        id: 1,
+        data: sharpen_recipe(1, UPGRADE_RATE_PRECISION + 1),
+    });
+
+    let result = process(&mut context, &[ix], &[]).await;
+    assert_eq!(error_code(result), u32::from(UpgradeError::InvalidSuccessRate));
+}
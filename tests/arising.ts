@@ -1,19 +1,30 @@
 import * as anchor from '@project-serum/anchor'
 import { Program } from '@project-serum/anchor'
-import { LAMPORTS_PER_SOL } from '@solana/web3.js'
+import { LAMPORTS_PER_SOL, SYSVAR_SLOT_HASHES_PUBKEY } from '@solana/web3.js'
 import { expect } from 'chai'
 import { MockFarmQuest, MockJobQuest, MockRaidQuest } from '../data/quests'
 import { MockForgeRecipe } from '../data/recipes'
 import { Arising } from '../target/types/arising'
 import {
     getProgramCharacterAccount,
+    getProgramCharacterAccounts,
+    getProgramCharacterEquipmentAccount,
+    getProgramCharacterMaterialsAccount,
+    getProgramCharacterSlotsAccount,
+    getMetadataAccount,
     getProgramConfigAccount,
     getProgramForgeRecipeAccount,
     getProgramQuestAccount,
     getTokenWalletAccount,
 } from '../data/accounts'
-import { mockMintNFT, waitUntilTimestamp } from './utils'
+import {
+    mockMintNFT,
+    mockNFTMetadata,
+    newCommitment,
+    waitUntilTimestamp,
+} from './utils'
 import { BASIC_MATERIAL } from '../data/basic_materials'
+import { RAW_MATERIALS } from '../data/raw_materials'
 
 describe('arising', () => {
     const payer = anchor.web3.Keypair.generate()
@@ -26,6 +37,8 @@ describe('arising', () => {
     const mint1 = anchor.web3.Keypair.generate()
     const mint2 = anchor.web3.Keypair.generate()
     const mint3 = anchor.web3.Keypair.generate()
+    const refresherMint = anchor.web3.Keypair.generate()
+    const collectionMint = anchor.web3.Keypair.generate()
 
     it('Should mint the mock tokens', async () => {
         const airdropSignature = await authority.connection.requestAirdrop(
@@ -43,6 +56,23 @@ describe('arising', () => {
         await mockMintNFT(payer, anchor.getProvider(), mint1)
         await mockMintNFT(payer, anchor.getProvider(), mint2)
         await mockMintNFT(payer, anchor.getProvider(), mint3)
+        await mockMintNFT(payer, anchor.getProvider(), refresherMint)
+        await mockMintNFT(payer, anchor.getProvider(), collectionMint)
+
+        await mockNFTMetadata(
+            payer,
+            anchor.getProvider(),
+            collectionMint.publicKey
+        )
+
+        for (const mint of [mint1, mint2, mint3]) {
+            await mockNFTMetadata(
+                payer,
+                anchor.getProvider(),
+                mint.publicKey,
+                collectionMint.publicKey
+            )
+        }
     })
 
     it('Initialize', async () => {
@@ -79,7 +109,8 @@ describe('arising', () => {
             .setPaused(false)
             .accounts({
                 config: config_program_address,
-                payer: authority.publicKey,
+                operator: null,
+                authority: authority.publicKey,
             })
             .rpc()
 
@@ -92,7 +123,8 @@ describe('arising', () => {
             .setPaused(true)
             .accounts({
                 config: config_program_address,
-                payer: authority.publicKey,
+                operator: null,
+                authority: authority.publicKey,
             })
             .rpc()
 
@@ -100,6 +132,44 @@ describe('arising', () => {
             config_program_address
         )
         expect(config.paused).to.eq(true)
+
+        await program.methods
+            .setPaused(false)
+            .accounts({
+                config: config_program_address,
+                operator: null,
+                authority: authority.publicKey,
+            })
+            .rpc()
+
+        config = await program.account.config.fetchNullable(
+            config_program_address
+        )
+        expect(config.paused).to.eq(false)
+    })
+
+    it('Set the characters collection', async () => {
+        const { account: config_program_address } =
+            await getProgramConfigAccount(program)
+
+        await program.methods
+            .setCharacterCollection(payer.publicKey)
+            .accounts({
+                config: config_program_address,
+                authority: authority.publicKey,
+                collectionMint: collectionMint.publicKey,
+            })
+            .rpc()
+
+        const config = await program.account.config.fetch(
+            config_program_address
+        )
+        expect(config.characterCollection.toBase58()).to.eq(
+            collectionMint.publicKey.toBase58()
+        )
+        expect(config.characterCreator.toBase58()).to.eq(
+            payer.publicKey.toBase58()
+        )
     })
 
     it('Add a fake mint and fetch the information', async () => {
@@ -113,8 +183,13 @@ describe('arising', () => {
             .addCharacter(mint1.publicKey, bump1)
             .accounts({
                 config: config_program_address,
+                operator: null,
                 payer: authority.publicKey,
-                character: mint1_address,
+                authority: authority.publicKey,
+                characterMint: mint1.publicKey,
+                characterMetadata: (await getMetadataAccount(mint1.publicKey))
+                    .account,
+                ...(await getProgramCharacterAccounts(mint1.publicKey, program)),
             })
             .rpc()
 
@@ -125,8 +200,13 @@ describe('arising', () => {
             .addCharacter(mint2.publicKey, bump2)
             .accounts({
                 config: config_program_address,
+                operator: null,
                 payer: authority.publicKey,
-                character: mint2_address,
+                authority: authority.publicKey,
+                characterMint: mint2.publicKey,
+                characterMetadata: (await getMetadataAccount(mint2.publicKey))
+                    .account,
+                ...(await getProgramCharacterAccounts(mint2.publicKey, program)),
             })
             .rpc()
 
@@ -137,8 +217,13 @@ describe('arising', () => {
             .addCharacter(mint3.publicKey, bump3)
             .accounts({
                 config: config_program_address,
+                operator: null,
                 payer: authority.publicKey,
-                character: mint3_address,
+                authority: authority.publicKey,
+                characterMint: mint3.publicKey,
+                characterMetadata: (await getMetadataAccount(mint3.publicKey))
+                    .account,
+                ...(await getProgramCharacterAccounts(mint3.publicKey, program)),
             })
             .rpc()
 
@@ -162,6 +247,9 @@ describe('arising', () => {
     })
 
     it('Set the initial stats for the initial mints', async () => {
+        const { account: config_program_address } =
+            await getProgramConfigAccount(program)
+
         const assignStats = { might: 2, speed: 2, intellect: 2 }
 
         const { account: mint1_address } = await getProgramCharacterAccount(
@@ -176,7 +264,8 @@ describe('arising', () => {
             .assignStatsCharacter(assignStats)
             .accounts({
                 payer: authority.publicKey,
-                character: mint1_address,
+                config: config_program_address,
+                ...(await getProgramCharacterAccounts(mint1.publicKey, program)),
                 characterTokenAccount: character1_token_account,
             })
             .rpc()
@@ -200,7 +289,8 @@ describe('arising', () => {
             .assignStatsCharacter(assignStats)
             .accounts({
                 payer: authority.publicKey,
-                character: mint2_address,
+                config: config_program_address,
+                ...(await getProgramCharacterAccounts(mint2.publicKey, program)),
                 characterTokenAccount: character2_token_account,
             })
             .rpc()
@@ -223,7 +313,8 @@ describe('arising', () => {
             .assignStatsCharacter(assignStats)
             .accounts({
                 payer: authority.publicKey,
-                character: mint3_address,
+                config: config_program_address,
+                ...(await getProgramCharacterAccounts(mint3.publicKey, program)),
                 characterTokenAccount: character3_token_account,
             })
             .rpc()
@@ -248,7 +339,9 @@ describe('arising', () => {
             .addForgeRecipe(bump, recipe.id, recipe)
             .accounts({
                 config: config_program_address,
+                operator: null,
                 payer: authority.publicKey,
+                authority: authority.publicKey,
                 forgeRecipe: recipe_account,
             })
             .rpc()
@@ -268,7 +361,9 @@ describe('arising', () => {
                 .addQuest(bump, quest.id, quest)
                 .accounts({
                     config: config_program_address,
+                    operator: null,
                     payer: authority.publicKey,
+                    authority: authority.publicKey,
                     quest: quest_account,
                 })
                 .rpc()
@@ -299,7 +394,8 @@ describe('arising', () => {
             .updateForgeRecipeAvailability(true)
             .accounts({
                 config: config_program_address,
-                payer: authority.publicKey,
+                operator: null,
+                authority: authority.publicKey,
                 forgeRecipe: recipe_account,
             })
             .rpc()
@@ -316,7 +412,8 @@ describe('arising', () => {
             .updateForgeRecipe(newRecipe)
             .accounts({
                 config: config_program_address,
-                payer: authority.publicKey,
+                operator: null,
+                authority: authority.publicKey,
                 forgeRecipe: recipe_account,
             })
             .rpc()
@@ -333,7 +430,8 @@ describe('arising', () => {
             .updateForgeRecipe(recipe)
             .accounts({
                 config: config_program_address,
-                payer: authority.publicKey,
+                operator: null,
+                authority: authority.publicKey,
                 forgeRecipe: recipe_account,
             })
             .rpc()
@@ -362,7 +460,8 @@ describe('arising', () => {
                 .updateQuestAvailability(true)
                 .accounts({
                     config: config_program_address,
-                    payer: authority.publicKey,
+                    operator: null,
+                    authority: authority.publicKey,
                     quest: quest_account,
                 })
                 .rpc()
@@ -379,7 +478,8 @@ describe('arising', () => {
                 .updateQuest(newQuest)
                 .accounts({
                     config: config_program_address,
-                    payer: authority.publicKey,
+                    operator: null,
+                    authority: authority.publicKey,
                     quest: quest_account,
                 })
                 .rpc()
@@ -396,7 +496,8 @@ describe('arising', () => {
                 .updateQuest(quest)
                 .accounts({
                     config: config_program_address,
-                    payer: authority.publicKey,
+                    operator: null,
+                    authority: authority.publicKey,
                     quest: quest_account,
                 })
                 .rpc()
@@ -404,6 +505,9 @@ describe('arising', () => {
     })
 
     it('Start a job quest and claim it', async () => {
+        const { account: config_program_address } =
+            await getProgramConfigAccount(program)
+
         const quest = MockJobQuest()
 
         const { account: quest_account } = await getProgramQuestAccount(
@@ -416,42 +520,68 @@ describe('arising', () => {
             program
         )
 
+        const { account: character_materials_account } =
+            await getProgramCharacterMaterialsAccount(mint1.publicKey, program)
+
+        const { account: character_slots_account } =
+            await getProgramCharacterSlotsAccount(mint1.publicKey, program)
+
+        const { account: character_equipment_account } =
+            await getProgramCharacterEquipmentAccount(mint1.publicKey, program)
+
         const { account: character_token_account } =
             await getTokenWalletAccount(authority.publicKey, mint1.publicKey)
 
+        const { reveal, commitment } = newCommitment()
+
         await program.methods
-            .startQuest(new anchor.BN(Math.random()))
+            .startQuest(commitment)
             .accounts({
                 character: character_account,
+                characterMaterials: character_materials_account,
+                characterSlots: character_slots_account,
+                characterEquipment: character_equipment_account,
                 characterTokenAccount: character_token_account,
+                config: config_program_address,
                 quest: quest_account,
+                slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
             })
             .rpc()
 
-        let character = await program.account.character.fetch(character_account)
+        const slots = await program.account.characterSlots.fetch(
+            character_slots_account
+        )
 
-        await waitUntilTimestamp(character.quest.cooldown.toNumber())
+        await waitUntilTimestamp(slots.quest.cooldown.toNumber())
 
         await program.methods
-            .claimQuest()
+            .claimQuest(reveal)
             .accounts({
                 character: character_account,
+                characterMaterials: character_materials_account,
+                characterSlots: character_slots_account,
+                characterEquipment: character_equipment_account,
                 characterTokenAccount: character_token_account,
+                config: config_program_address,
                 quest: quest_account,
+                slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
             })
             .rpc()
 
-        character = character = await program.account.character.fetch(
-            character_account
+        const materials = await program.account.characterMaterials.fetch(
+            character_materials_account
         )
 
-        expect(character.raw).to.deep.eq([
+        expect(materials.raw).to.deep.eq([
             100, 100, 0, 100, 0, 100, 100, 100, 100, 100, 100, 100, 0, 0, 0, 0,
-            0,
+            0, 0, 0, 0,
         ])
     })
 
     it('Start a farm quest and claim it', async () => {
+        const { account: config_program_address } =
+            await getProgramConfigAccount(program)
+
         const quest = MockFarmQuest()
 
         const { account: quest_account } = await getProgramQuestAccount(
@@ -464,42 +594,68 @@ describe('arising', () => {
             program
         )
 
+        const { account: character_materials_account } =
+            await getProgramCharacterMaterialsAccount(mint1.publicKey, program)
+
+        const { account: character_slots_account } =
+            await getProgramCharacterSlotsAccount(mint1.publicKey, program)
+
+        const { account: character_equipment_account } =
+            await getProgramCharacterEquipmentAccount(mint1.publicKey, program)
+
         const { account: character_token_account } =
             await getTokenWalletAccount(authority.publicKey, mint1.publicKey)
 
+        const { reveal, commitment } = newCommitment()
+
         await program.methods
-            .startQuest(new anchor.BN(Math.random()))
+            .startQuest(commitment)
             .accounts({
                 character: character_account,
+                characterMaterials: character_materials_account,
+                characterSlots: character_slots_account,
+                characterEquipment: character_equipment_account,
                 characterTokenAccount: character_token_account,
+                config: config_program_address,
                 quest: quest_account,
+                slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
             })
             .rpc()
 
-        let character = await program.account.character.fetch(character_account)
+        const slots = await program.account.characterSlots.fetch(
+            character_slots_account
+        )
 
-        await waitUntilTimestamp(character.quest.cooldown.toNumber())
+        await waitUntilTimestamp(slots.quest.cooldown.toNumber())
 
         await program.methods
-            .claimQuest()
+            .claimQuest(reveal)
             .accounts({
                 character: character_account,
+                characterMaterials: character_materials_account,
+                characterSlots: character_slots_account,
+                characterEquipment: character_equipment_account,
                 characterTokenAccount: character_token_account,
+                config: config_program_address,
                 quest: quest_account,
+                slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
             })
             .rpc()
 
-        character = character = await program.account.character.fetch(
-            character_account
+        const materials = await program.account.characterMaterials.fetch(
+            character_materials_account
         )
 
-        expect(character.raw).to.deep.eq([
+        expect(materials.raw).to.deep.eq([
             200, 200, 0, 100, 0, 100, 100, 100, 100, 100, 100, 100, 0, 0, 0, 0,
-            0,
+            0, 0, 0, 0,
         ])
     })
 
     it('Start a raid quest and claim it', async () => {
+        const { account: config_program_address } =
+            await getProgramConfigAccount(program)
+
         const quest = MockRaidQuest()
 
         const { account: quest_account } = await getProgramQuestAccount(
@@ -512,36 +668,83 @@ describe('arising', () => {
             program
         )
 
+        const { account: character_materials_account } =
+            await getProgramCharacterMaterialsAccount(mint1.publicKey, program)
+
+        const { account: character_slots_account } =
+            await getProgramCharacterSlotsAccount(mint1.publicKey, program)
+
+        const { account: character_equipment_account } =
+            await getProgramCharacterEquipmentAccount(mint1.publicKey, program)
+
         const { account: character_token_account } =
             await getTokenWalletAccount(authority.publicKey, mint1.publicKey)
 
+        // A mob without stats, level or attributes deals 1 damage per hit and strikes second,
+        // so the character always wins and the raid always rewards it
+        const raid = await program.account.quest.fetch(quest_account)
+        raid.mobLevel = 0
+        raid.mobBaseStats = { might: 0, speed: 0, intellect: 0 }
+        raid.mobBaseAttributes = { atk: 0, def: 0, range: 0, magAtk: 0, magDef: 0, rate: 0 }
+
+        await program.methods
+            .updateQuest(raid)
+            .accounts({
+                config: config_program_address,
+                operator: null,
+                authority: authority.publicKey,
+                quest: quest_account,
+            })
+            .rpc()
+
+        const { reveal, commitment } = newCommitment()
+
         await program.methods
-            .startQuest(new anchor.BN(Math.random()))
+            .startQuest(commitment)
             .accounts({
                 character: character_account,
+                characterMaterials: character_materials_account,
+                characterSlots: character_slots_account,
+                characterEquipment: character_equipment_account,
                 characterTokenAccount: character_token_account,
+                config: config_program_address,
                 quest: quest_account,
+                slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
             })
             .rpc()
 
-        let character = await program.account.character.fetch(character_account)
+        const slots = await program.account.characterSlots.fetch(
+            character_slots_account
+        )
 
-        await waitUntilTimestamp(character.quest.cooldown.toNumber())
+        await waitUntilTimestamp(slots.quest.cooldown.toNumber())
 
         await program.methods
-            .claimQuest()
+            .claimQuest(reveal)
             .accounts({
                 character: character_account,
+                characterMaterials: character_materials_account,
+                characterSlots: character_slots_account,
+                characterEquipment: character_equipment_account,
                 characterTokenAccount: character_token_account,
+                config: config_program_address,
                 quest: quest_account,
+                slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
             })
             .rpc()
 
-        character = character = await program.account.character.fetch(
+        const character = await program.account.character.fetch(
             character_account
         )
 
-        // TODO check level
+        const materials = await program.account.characterMaterials.fetch(
+            character_materials_account
+        )
+
+        // The loot is rolled between half and the full reward amount
+        expect(character.experience.toNumber()).to.eq(500)
+        expect(character.level).to.eq(0)
+        expect(materials.raw[RAW_MATERIALS.BONES - 1]).to.be.within(225, 250)
     })
 
     it('Refresh the pool points', async () => {
@@ -582,6 +785,64 @@ describe('arising', () => {
         })
     })
 
+    it('Refresh the pool points burning a refresher token', async () => {
+        const { account: config_program_address } =
+            await getProgramConfigAccount(program)
+
+        await program.methods
+            .setRefresherMint()
+            .accounts({
+                config: config_program_address,
+                operator: null,
+                authority: authority.publicKey,
+                refresherMint: refresherMint.publicKey,
+            })
+            .rpc()
+
+        const config = await program.account.config.fetch(
+            config_program_address
+        )
+        expect(config.refresherMint.toBase58()).to.eq(
+            refresherMint.publicKey.toBase58()
+        )
+
+        const { account: character_account } = await getProgramCharacterAccount(
+            mint2.publicKey,
+            program
+        )
+
+        const { account: character_token_account } =
+            await getTokenWalletAccount(authority.publicKey, mint2.publicKey)
+
+        const { account: refresher_token_account } =
+            await getTokenWalletAccount(
+                authority.publicKey,
+                refresherMint.publicKey
+            )
+
+        await program.methods
+            .performRefreshWithToken()
+            .accounts({
+                character: character_account,
+                characterTokenAccount: character_token_account,
+                config: config_program_address,
+                refresherMint: refresherMint.publicKey,
+                refresherTokenAccount: refresher_token_account,
+            })
+            .rpc()
+
+        const balance =
+            await authority.connection.getTokenAccountBalance(
+                refresher_token_account
+            )
+        expect(balance.value.amount).to.eq('0')
+
+        const character = await program.account.character.fetch(
+            character_account
+        )
+        expect(character.lastRefreshWithRefresher.toNumber()).to.be.gt(0)
+    })
+
     it('Start a forge recipe and claim it', async () => {
         const recipe = MockForgeRecipe()
 
@@ -590,7 +851,7 @@ describe('arising', () => {
             program
         )
 
-        const { account: character_account } = await getProgramCharacterAccount(
+        const characterAccounts = await getProgramCharacterAccounts(
             mint1.publicKey,
             program
         )
@@ -598,32 +859,43 @@ describe('arising', () => {
         const { account: character_token_account } =
             await getTokenWalletAccount(authority.publicKey, mint1.publicKey)
 
+        const { account: config_program_address } =
+            await getProgramConfigAccount(program)
+
         await program.methods
             .startForge()
             .accounts({
-                character: character_account,
+                character: characterAccounts.character,
+                characterMaterials: characterAccounts.characterMaterials,
+                characterSlots: characterAccounts.characterSlots,
                 characterTokenAccount: character_token_account,
+                config: config_program_address,
                 forgeRecipe: recipe_account,
             })
             .rpc()
 
-        let character = await program.account.character.fetch(character_account)
+        const slots = await program.account.characterSlots.fetch(
+            characterAccounts.characterSlots
+        )
 
-        await waitUntilTimestamp(character.forge.cooldown.toNumber())
+        await waitUntilTimestamp(slots.forge.cooldown.toNumber())
 
         await program.methods
             .claimForge()
             .accounts({
-                character: character_account,
+                character: characterAccounts.character,
+                characterMaterials: characterAccounts.characterMaterials,
+                characterSlots: characterAccounts.characterSlots,
                 characterTokenAccount: character_token_account,
+                config: config_program_address,
                 forgeRecipe: recipe_account,
             })
             .rpc()
 
-        character = character = await program.account.character.fetch(
-            character_account
+        const materials = await program.account.characterMaterials.fetch(
+            characterAccounts.characterMaterials
         )
 
-        expect(character.basic[BASIC_MATERIAL.WOOD_PLANK - 1]).to.eq(1)
+        expect(materials.basic[BASIC_MATERIAL.WOOD_PLANK - 1]).to.eq(1)
     })
 })
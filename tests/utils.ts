@@ -10,13 +10,26 @@ import {
 
 import {
     Keypair,
+    PublicKey,
     SystemProgram,
     TransactionInstruction,
     VersionedTransaction,
     TransactionMessage,
 } from '@solana/web3.js'
 
-import { getTokenWalletAccount } from '../data/accounts'
+import {
+    createCreateMasterEditionV3Instruction,
+    createCreateMetadataAccountV3Instruction,
+    createVerifyCollectionInstruction,
+} from '@metaplex-foundation/mpl-token-metadata'
+
+import { createHash, randomBytes } from 'crypto'
+
+import {
+    getMasterEditionAccount,
+    getMetadataAccount,
+    getTokenWalletAccount,
+} from '../data/accounts'
 
 // Helper function to mock a NFT mint.
 // Creates an associated account with balance of 1 to the provider
@@ -93,6 +106,104 @@ export const mockMintNFT = async (
     await provider.connection.sendTransaction(transaction)
 }
 
+// Helper function to mock the metadata of a mocked NFT, created and verified by the payer.
+// Without a collection the NFT becomes a collection with its master edition,
+// otherwise the NFT is verified in the given collection.
+export const mockNFTMetadata = async (
+    payer: Keypair,
+    provider: Provider,
+    mint: PublicKey,
+    collection?: PublicKey
+): Promise<void> => {
+    const { account: metadata } = await getMetadataAccount(mint)
+
+    const instructions: TransactionInstruction[] = []
+
+    instructions.push(
+        createCreateMetadataAccountV3Instruction(
+            {
+                metadata,
+                mint,
+                mintAuthority: payer.publicKey,
+                payer: payer.publicKey,
+                updateAuthority: payer.publicKey,
+            },
+            {
+                createMetadataAccountArgsV3: {
+                    data: {
+                        name: 'Arising',
+                        symbol: 'ARISING',
+                        uri: '',
+                        sellerFeeBasisPoints: 0,
+                        creators: [
+                            {
+                                address: payer.publicKey,
+                                verified: true,
+                                share: 100,
+                            },
+                        ],
+                        collection: collection
+                            ? { verified: false, key: collection }
+                            : null,
+                        uses: null,
+                    },
+                    isMutable: true,
+                    collectionDetails: null,
+                },
+            }
+        )
+    )
+
+    if (collection) {
+        const { account: collectionMetadata } = await getMetadataAccount(
+            collection
+        )
+        const { account: collectionEdition } = await getMasterEditionAccount(
+            collection
+        )
+
+        instructions.push(
+            createVerifyCollectionInstruction({
+                metadata,
+                collectionAuthority: payer.publicKey,
+                payer: payer.publicKey,
+                collectionMint: collection,
+                collection: collectionMetadata,
+                collectionMasterEditionAccount: collectionEdition,
+            })
+        )
+    } else {
+        const { account: edition } = await getMasterEditionAccount(mint)
+
+        instructions.push(
+            createCreateMasterEditionV3Instruction(
+                {
+                    edition,
+                    mint,
+                    updateAuthority: payer.publicKey,
+                    mintAuthority: payer.publicKey,
+                    payer: payer.publicKey,
+                    metadata,
+                },
+                { createMasterEditionArgs: { maxSupply: 0 } }
+            )
+        )
+    }
+
+    const message = new TransactionMessage({
+        payerKey: payer.publicKey,
+        recentBlockhash: (await provider.connection.getLatestBlockhash())
+            .blockhash,
+        instructions,
+    }).compileToLegacyMessage()
+
+    const transaction = new VersionedTransaction(message)
+
+    transaction.sign([payer])
+
+    await provider.connection.sendTransaction(transaction)
+}
+
 export const waitUntilTimestamp = (timestamp: number): Promise<void> => {
     return new Promise((resolve) => {
         const interval = setInterval(() => {
@@ -107,3 +218,11 @@ export const waitUntilTimestamp = (timestamp: number): Promise<void> => {
         }
     })
 }
+
+// Returns a random secret to reveal on claim and the commitment to store on start.
+export const newCommitment = (): { reveal: number[]; commitment: number[] } => {
+    const reveal = randomBytes(32)
+    const commitment = createHash('sha256').update(reveal).digest()
+
+    return { reveal: Array.from(reveal), commitment: Array.from(commitment) }
+}